@@ -1,5 +1,4 @@
 mod dkls23;
 mod simple;
 
-pub use dkls23::*;
 pub use simple::*;
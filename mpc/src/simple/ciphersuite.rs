@@ -0,0 +1,610 @@
+use std::marker::PhantomData;
+
+use ark_bls12_381::{Bls12_381, Fr, G1Affine, G2Affine};
+use ark_ec::{pairing::Pairing, AffineRepr, CurveGroup};
+use ark_ff::{PrimeField, Zero};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use hkdf::Hkdf;
+use sha2::{Digest, Sha256};
+
+use super::{hash_to_curve, hash_to_g2, MPCError};
+
+/// ```md
+/// The two variants of the IETF BLS signature ciphersuites (draft-irtf-cfrg-bls-signature):
+///
+/// min-pk:  PK = sk * g1 ∈ G1 (48 bytes),  sig = sk * H(msg) ∈ G2 (96 bytes)
+/// min-sig: PK = sk * g2 ∈ G2 (96 bytes),  sig = sk * H(msg) ∈ G1 (48 bytes)
+///
+/// verified with e(PK, H(msg)) == e(g1, sig), or e(H(msg), PK) == e(sig, g2).
+/// ```
+///
+/// Ethereum consensus uses min-pk. `MPCWallet` signs with min-sig.
+pub trait Variant {
+    type PublicKey: AffineRepr<ScalarField = Fr> + CanonicalSerialize + CanonicalDeserialize;
+    type Signature: AffineRepr<ScalarField = Fr> + CanonicalSerialize + CanonicalDeserialize;
+
+    /// The group signatures live in, as it appears in the ciphersuite ID
+    const SIGNATURE_GROUP: &'static str;
+    /// Compressed sizes, in bytes
+    const PUBLIC_KEY_SIZE: usize;
+    const SIGNATURE_SIZE: usize;
+
+    fn hash(message: &[u8], dst: &[u8]) -> Result<Self::Signature, MPCError>;
+
+    /// e(PK, H(msg)) == e(g, sig), with the pairing arguments in the right order
+    fn pairing_check(
+        public_key: &Self::PublicKey,
+        message_point: &Self::Signature,
+        signature: &Self::Signature,
+    ) -> bool;
+}
+
+/// Public keys on G1, signatures on G2
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MinPk;
+
+/// Public keys on G2, signatures on G1
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MinSig;
+
+impl Variant for MinPk {
+    type PublicKey = G1Affine;
+    type Signature = G2Affine;
+
+    const SIGNATURE_GROUP: &'static str = "BLS12381G2";
+    const PUBLIC_KEY_SIZE: usize = 48;
+    const SIGNATURE_SIZE: usize = 96;
+
+    fn hash(message: &[u8], dst: &[u8]) -> Result<G2Affine, MPCError> {
+        hash_to_g2(message, dst)
+    }
+
+    fn pairing_check(
+        public_key: &G1Affine,
+        message_point: &G2Affine,
+        signature: &G2Affine,
+    ) -> bool {
+        // e(PK, H(msg)) * e(-g1, sig) == 1
+        Bls12_381::multi_pairing(
+            [*public_key, -G1Affine::generator()],
+            [*message_point, *signature],
+        )
+        .is_zero()
+    }
+}
+
+impl Variant for MinSig {
+    type PublicKey = G2Affine;
+    type Signature = G1Affine;
+
+    const SIGNATURE_GROUP: &'static str = "BLS12381G1";
+    const PUBLIC_KEY_SIZE: usize = 96;
+    const SIGNATURE_SIZE: usize = 48;
+
+    fn hash(message: &[u8], dst: &[u8]) -> Result<G1Affine, MPCError> {
+        hash_to_curve(message, dst)
+    }
+
+    fn pairing_check(
+        public_key: &G2Affine,
+        message_point: &G1Affine,
+        signature: &G1Affine,
+    ) -> bool {
+        // e(H(msg), PK) * e(sig, -g2) == 1
+        Bls12_381::multi_pairing(
+            [*message_point, *signature],
+            [*public_key, -G2Affine::generator()],
+        )
+        .is_zero()
+    }
+}
+
+/// How a ciphersuite protects against rogue-key attacks on aggregate signatures
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Scheme {
+    /// Plain signatures: aggregates are only safe over distinct messages
+    Basic,
+    /// Every message is signed as PK || msg
+    MessageAugmentation,
+    /// Every public key comes with a proof of possession of its secret key (`pop_prove`)
+    ProofOfPossession,
+}
+
+impl Scheme {
+    fn tag(&self) -> &'static str {
+        match self {
+            Scheme::Basic => "NUL_",
+            Scheme::MessageAugmentation => "AUG_",
+            Scheme::ProofOfPossession => "POP_",
+        }
+    }
+}
+
+/// A BLS ciphersuite: a variant (`MinPk` or `MinSig`) and a scheme, e.g.
+/// `Ciphersuite::<MinPk>::new(Scheme::ProofOfPossession)` is the one Ethereum consensus uses,
+/// `BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_POP_`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Ciphersuite<V> {
+    pub scheme: Scheme,
+    variant: PhantomData<V>,
+}
+
+impl<V: Variant> Ciphersuite<V> {
+    pub fn new(scheme: Scheme) -> Self {
+        Ciphersuite {
+            scheme,
+            variant: PhantomData,
+        }
+    }
+
+    /// The ciphersuite ID, used as the domain separation tag of `sign` and `verify`
+    pub fn dst(&self) -> Vec<u8> {
+        format!(
+            "BLS_SIG_{}_XMD:SHA-256_SSWU_RO_{}",
+            V::SIGNATURE_GROUP,
+            self.scheme.tag()
+        )
+        .into_bytes()
+    }
+
+    /// The domain separation tag of proofs of possession, distinct from `dst` so that a proof
+    /// is never a signature on the serialized public key
+    pub fn pop_dst(&self) -> Vec<u8> {
+        format!("BLS_POP_{}_XMD:SHA-256_SSWU_RO_POP_", V::SIGNATURE_GROUP).into_bytes()
+    }
+
+    pub fn sk_to_pk(secret_key: &Fr) -> V::PublicKey {
+        (V::PublicKey::generator() * secret_key).into_affine()
+    }
+
+    pub fn sign(&self, secret_key: &Fr, message: &[u8]) -> Result<V::Signature, MPCError> {
+        let message = self.augment(&Self::sk_to_pk(secret_key), message)?;
+        let message_point = V::hash(&message, &self.dst())?;
+        Ok((message_point * secret_key).into_affine())
+    }
+
+    pub fn verify(
+        &self,
+        public_key: &V::PublicKey,
+        message: &[u8],
+        signature: &V::Signature,
+    ) -> Result<(), MPCError> {
+        key_validate(public_key)?;
+        if !in_subgroup(signature) {
+            return Err(MPCError::VerificationFailed);
+        }
+
+        let message = self.augment(public_key, message)?;
+        let message_point = V::hash(&message, &self.dst())?;
+        if V::pairing_check(public_key, &message_point, signature) {
+            Ok(())
+        } else {
+            Err(MPCError::VerificationFailed)
+        }
+    }
+
+    /// PopProve: a signature on the compressed public key, under `pop_dst`.
+    /// Only the `ProofOfPossession` scheme defines proofs, the others fail with `UnsupportedScheme`.
+    pub fn pop_prove(&self, secret_key: &Fr) -> Result<V::Signature, MPCError> {
+        self.require_pop()?;
+        let public_key = encode(&Self::sk_to_pk(secret_key))?;
+        let message_point = V::hash(&public_key, &self.pop_dst())?;
+        Ok((message_point * secret_key).into_affine())
+    }
+
+    /// PopVerify: the holder of `public_key` knows its secret key
+    pub fn pop_verify(
+        &self,
+        public_key: &V::PublicKey,
+        proof: &V::Signature,
+    ) -> Result<(), MPCError> {
+        self.require_pop()?;
+        key_validate(public_key)?;
+        if !in_subgroup(proof) {
+            return Err(MPCError::VerificationFailed);
+        }
+
+        let message_point = V::hash(&encode(public_key)?, &self.pop_dst())?;
+        if V::pairing_check(public_key, &message_point, proof) {
+            Ok(())
+        } else {
+            Err(MPCError::VerificationFailed)
+        }
+    }
+
+    pub fn decode_public_key(bytes: &[u8]) -> Result<V::PublicKey, MPCError> {
+        let public_key = decode::<V::PublicKey>(bytes, V::PUBLIC_KEY_SIZE)?;
+        key_validate(&public_key)?;
+        Ok(public_key)
+    }
+
+    pub fn decode_signature(bytes: &[u8]) -> Result<V::Signature, MPCError> {
+        decode(bytes, V::SIGNATURE_SIZE)
+    }
+
+    fn require_pop(&self) -> Result<(), MPCError> {
+        match self.scheme {
+            Scheme::ProofOfPossession => Ok(()),
+            scheme => Err(MPCError::UnsupportedScheme(scheme)),
+        }
+    }
+
+    fn augment(&self, public_key: &V::PublicKey, message: &[u8]) -> Result<Vec<u8>, MPCError> {
+        Ok(match self.scheme {
+            Scheme::MessageAugmentation => [encode(public_key)?, message.to_vec()].concat(),
+            Scheme::Basic | Scheme::ProofOfPossession => message.to_vec(),
+        })
+    }
+}
+
+/// ```md
+/// KeyGen(IKM, key_info), with HKDF-SHA-256 and L = ceil(3 * ceil(log2(r)) / 16) = 48:
+///
+/// salt = "BLS-SIG-KEYGEN-SALT-"
+/// loop:
+///     salt = SHA-256(salt)
+///     PRK  = HKDF-Extract(salt, IKM || I2OSP(0, 1))
+///     OKM  = HKDF-Expand(PRK, key_info || I2OSP(L, 2), L)
+///     SK   = OS2IP(OKM) mod r
+/// until SK != 0
+/// ```
+///
+/// `ikm` must be at least 32 bytes of secret randomness. This is also EIP-2333's master key derivation.
+pub fn key_gen(ikm: &[u8], key_info: &[u8]) -> Result<Fr, MPCError> {
+    const L: usize = 48;
+    if ikm.len() < 32 {
+        return Err(MPCError::InvalidKeyMaterial(ikm.len()));
+    }
+
+    let ikm = [ikm, &[0]].concat();
+    let info = [key_info, &(L as u16).to_be_bytes()].concat();
+    let mut salt = b"BLS-SIG-KEYGEN-SALT-".to_vec();
+    loop {
+        salt = Sha256::digest(&salt).to_vec();
+        let mut okm = [0u8; L];
+        Hkdf::<Sha256>::new(Some(&salt), &ikm)
+            .expand(&info, &mut okm)
+            .expect("48 bytes is a valid HKDF-SHA-256 output length");
+
+        let secret_key = Fr::from_be_bytes_mod_order(&okm);
+        if !secret_key.is_zero() {
+            return Ok(secret_key);
+        }
+    }
+}
+
+/// KeyValidate: the public key is in the prime-order subgroup and is not the identity
+pub fn key_validate<P: AffineRepr>(public_key: &P) -> Result<(), MPCError> {
+    if public_key.is_zero() || !in_subgroup(public_key) {
+        return Err(MPCError::InvalidPublicKey);
+    }
+    Ok(())
+}
+
+/// r * P == 0: points from `decode` are always checked, points built in memory may not be
+fn in_subgroup<P: AffineRepr>(point: &P) -> bool {
+    point
+        .mul_bigint(<P::ScalarField as PrimeField>::MODULUS)
+        .is_zero()
+}
+
+/// The compressed encoding of a point (48 bytes on G1, 96 on G2), in the format of the
+/// ciphersuites: big-endian x with the compression, infinity and sign flags in the top 3 bits
+pub fn encode<P: CanonicalSerialize>(point: &P) -> Result<Vec<u8>, MPCError> {
+    let mut bytes = Vec::with_capacity(point.compressed_size());
+    point
+        .serialize_compressed(&mut bytes)
+        .map_err(|e| MPCError::SerializationError(e.to_string()))?;
+    Ok(bytes)
+}
+
+/// Decodes a compressed point, checking it is on the curve and in the prime-order subgroup
+fn decode<P: CanonicalDeserialize>(bytes: &[u8], size: usize) -> Result<P, MPCError> {
+    if bytes.len() != size {
+        return Err(MPCError::SerializationError(format!(
+            "expected {size} bytes, got {}",
+            bytes.len()
+        )));
+    }
+    P::deserialize_compressed(bytes).map_err(|e| MPCError::SerializationError(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_ff::BigInteger;
+    use ark_std::{test_rng, UniformRand};
+
+    use super::*;
+    use crate::MPCWallet;
+
+    fn fr(hex: &str) -> Fr {
+        Fr::from_be_bytes_mod_order(&hex::decode(hex).unwrap())
+    }
+
+    #[test]
+    fn test_ciphersuite_ids() {
+        let min_pk = Ciphersuite::<MinPk>::new(Scheme::ProofOfPossession);
+        assert_eq!(min_pk.dst(), b"BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_POP_");
+        assert_eq!(
+            min_pk.pop_dst(),
+            b"BLS_POP_BLS12381G2_XMD:SHA-256_SSWU_RO_POP_"
+        );
+
+        let min_sig = Ciphersuite::<MinSig>::new(Scheme::MessageAugmentation);
+        assert_eq!(
+            min_sig.dst(),
+            b"BLS_SIG_BLS12381G1_XMD:SHA-256_SSWU_RO_AUG_"
+        );
+        assert_eq!(
+            Ciphersuite::<MinSig>::new(Scheme::Basic).dst(),
+            crate::DEFAULT_DST
+        );
+    }
+
+    /// EIP-2333 test cases 0 to 2: master_SK = KeyGen(seed, "")
+    #[test]
+    fn test_key_gen_vectors() {
+        let vectors = [
+            (
+                "c55257c360c07c72029aebc1b53c05ed0362ada38ead3e3e9efa3708e53495531f09a6987599d18264c1e1c92f2cf141630c7a3c4ab7c81b2f001698e7463b04",
+                "6083874454709270928345386274498605044986640685124978867557563392430687146096",
+            ),
+            (
+                "3141592653589793238462643383279502884197169399375105820974944592",
+                "29757020647961307431480504535336562678282505419141012933316116377660817309383",
+            ),
+            (
+                "0099FF991111002299DD7744EE3355BBDD8844115566CC55663355668888CC00",
+                "27580842291869792442942448775674722299803720648445448686099262467207037398656",
+            ),
+        ];
+
+        for (seed, master_sk) in vectors {
+            let secret_key = key_gen(&hex::decode(seed).unwrap(), b"").unwrap();
+            assert_eq!(secret_key.into_bigint().to_string(), master_sk);
+        }
+
+        assert!(matches!(
+            key_gen(&[0; 31], b""),
+            Err(MPCError::InvalidKeyMaterial(31))
+        ));
+        let ikm = [7; 32];
+        assert_ne!(key_gen(&ikm, b"").unwrap(), key_gen(&ikm, b"info").unwrap());
+    }
+
+    /// Ethereum consensus spec tests, bls/sign and bls/verify (min-pk, proof of possession)
+    #[test]
+    fn test_ethereum_vectors() {
+        let suite = Ciphersuite::<MinPk>::new(Scheme::ProofOfPossession);
+        let secret_key = fr("263dbd792f5b1be47ed85f8938c0f29586af0d3ac7b977f21c278fe1462040e3");
+        let public_key = hex::decode("a491d1b0ecd9bb917989f0e74f0dea0422eac4a873e5e2644f368dffb9a6e20fd6e10c1b77654d067c0618f6e5a7f79a").unwrap();
+        let message = [0u8; 32];
+        let signature = hex::decode("b6ed936746e01f8ecf281f020953fbf1f01debd5657c4a383940b020b26507f6076334f91e2366c96e9ab279fb5158090352ea1c5b0c9274504f4f0e7053af24802e51e4568d164fe986834f41e55c8e850ce1f98458c0cfc9ab380b55285a55").unwrap();
+
+        let pk = Ciphersuite::<MinPk>::sk_to_pk(&secret_key);
+        assert_eq!(encode(&pk).unwrap(), public_key);
+        let sig = suite.sign(&secret_key, &message).unwrap();
+        assert_eq!(encode(&sig).unwrap(), signature);
+
+        let pk = Ciphersuite::<MinPk>::decode_public_key(&public_key).unwrap();
+        let sig = Ciphersuite::<MinPk>::decode_signature(&signature).unwrap();
+        assert!(suite.verify(&pk, &message, &sig).is_ok());
+        assert!(matches!(
+            suite.verify(&pk, &[1u8; 32], &sig),
+            Err(MPCError::VerificationFailed)
+        ));
+
+        // bls/sign with another key and more messages
+        let secret_key = fr("47b8192d77bf871b62e87859d653922725724a5c031afeabc60bcef5ff665138");
+        let public_key = hex::decode("b301803f8b5ac4a1133581fc676dfedc60d891dd5fa99028805e5ea5b08d3491af75d0707adab3b70c6a6a580217bf81").unwrap();
+        assert_eq!(
+            encode(&Ciphersuite::<MinPk>::sk_to_pk(&secret_key)).unwrap(),
+            public_key
+        );
+        for (message, signature) in [
+            ([0x00; 32], "b23c46be3a001c63ca711f87a005c200cc550b9429d5f4eb38d74322144f1b63926da3388979e5321012fb1a0526bcd100b5ef5fe72628ce4cd5e904aeaa3279527843fae5ca9ca675f4f51ed8f83bbf7155da9ecc9663100a885d5dc6df96d9"),
+            ([0x56; 32], "af1390c3c47acdb37131a51216da683c509fce0e954328a59f93aebda7e4ff974ba208d9a4a2a2389f892a9d418d618418dd7f7a6bc7aa0da999a9d3a5b815bc085e14fd001f6a1948768a3f4afefc8b8240dda329f984cb345c6363272ba4fe"),
+        ] {
+            let signature = hex::decode(signature).unwrap();
+            assert_eq!(
+                encode(&suite.sign(&secret_key, &message).unwrap()).unwrap(),
+                signature
+            );
+            let pk = Ciphersuite::<MinPk>::decode_public_key(&public_key).unwrap();
+            let sig = Ciphersuite::<MinPk>::decode_signature(&signature).unwrap();
+            assert!(suite.verify(&pk, &message, &sig).is_ok());
+        }
+    }
+
+    /// Ethereum consensus spec tests with invalid points: bls/verify
+    /// (verify_infinity_pubkey_and_infinity_signature) and the G1 deserialization cases
+    #[test]
+    fn test_ethereum_invalid_points() {
+        let suite = Ciphersuite::<MinPk>::new(Scheme::ProofOfPossession);
+
+        // the point at infinity is not a valid public key, whatever the signature
+        let mut infinity = vec![0u8; 48];
+        infinity[0] = 0xc0;
+        assert!(matches!(
+            Ciphersuite::<MinPk>::decode_public_key(&infinity),
+            Err(MPCError::InvalidPublicKey)
+        ));
+        let mut infinity_signature = vec![0u8; 96];
+        infinity_signature[0] = 0xc0;
+        let infinity_signature =
+            Ciphersuite::<MinPk>::decode_signature(&infinity_signature).unwrap();
+        assert!(matches!(
+            suite.verify(&G1Affine::zero(), &[0x12; 32], &infinity_signature),
+            Err(MPCError::InvalidPublicKey)
+        ));
+        assert!(matches!(
+            suite.pop_verify(&G1Affine::zero(), &infinity_signature),
+            Err(MPCError::InvalidPublicKey)
+        ));
+
+        for public_key in [
+            // x = p
+            "9a0111ea397fe69a4b1ba7b6434bacd764774b84f38512bf6730d2a0f6b0f6241eabfffeb153ffffb9feffffffffaaab",
+            // x > p
+            "9a0111ea397fe69a4b1ba7b6434bacd764774b84f38512bf6730d2a0f6b0f6241eabfffeb153ffffb9feffffffffaaac",
+            // infinity with the sign flag set
+            "e00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000",
+            // infinity flag with a non-zero x
+            "c01000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000",
+            // no compression flag
+            "000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000",
+        ] {
+            assert!(
+                Ciphersuite::<MinPk>::decode_public_key(&hex::decode(public_key).unwrap()).is_err(),
+                "{public_key}"
+            );
+        }
+        let signature = hex::decode("b6ed936746e01f8ecf281f020953fbf1f01debd5657c4a383940b020b26507f6076334f91e2366c96e9ab279fb5158090352ea1c5b0c9274504f4f0e7053af24802e51e4568d164fe986834f41e55c8e850ce1f98458c0cfc9ab380b55285a55").unwrap();
+        assert!(Ciphersuite::<MinPk>::decode_signature(&signature[..95]).is_err());
+    }
+
+    /// Vectors of other implementations: Chia's BasicSchemeMPL (min-pk, NUL) test vectors,
+    /// and the public key of drand's quicknet, a min-sig network (bls-unchained-g1-rfc9380)
+    #[test]
+    fn test_other_implementation_vectors() {
+        let suite = Ciphersuite::<MinPk>::new(Scheme::Basic);
+        let secret_key = fr("4a353be3dac091a0a7e640620372f5e1e2e4401717c1e79cac6ffba8f6905604");
+        let public_key = Ciphersuite::<MinPk>::sk_to_pk(&secret_key);
+        assert_eq!(
+            hex::encode(encode(&public_key).unwrap()),
+            "85695fcbc06cc4c4c9451f4dce21cbf8de3e5a13bf48f44cdbb18e2038ba7b8bb1632d7911ef1e2e08749bddbf165352"
+        );
+        let signature = suite.sign(&secret_key, &[7, 8, 9]).unwrap();
+        assert_eq!(
+            hex::encode(encode(&signature).unwrap()),
+            "b8faa6d6a3881c9fdbad803b170d70ca5cbf1e6ba5a586262df368c75acd1d1ffa3ab6ee21c71f844494659878f5eb230c958dd576b08b8564aad2ee0992e85a1e565f299cd53a285de729937f70dc176a1f01432129bb2b94d3d5031f8065a1"
+        );
+        assert!(suite.verify(&public_key, &[7, 8, 9], &signature).is_ok());
+
+        let quicknet = "83cf0f2896adee7eb8b5f01fcad3912212c437e0073e911fb90022d3e760183c8c4b450b6a0a6c3ac6a5776a2d1064510d1fec758c921cc22b0e17e63aaf4bcb5ed66304de9cf809bd274ca73bab4af5a6e9c76a4bc09e76eae8991ef5ece45a";
+        let public_key =
+            Ciphersuite::<MinSig>::decode_public_key(&hex::decode(quicknet).unwrap()).unwrap();
+        assert_eq!(hex::encode(encode(&public_key).unwrap()), quicknet);
+    }
+
+    #[test]
+    fn test_encodings() {
+        // the compressed generators, as in the ZCash serialization format
+        assert_eq!(
+            hex::encode(encode(&G1Affine::generator()).unwrap()),
+            "97f1d3a73197d7942695638c4fa9ac0fc3688c4f9774b905a14e3a3f171bac586c55e83ff97a1aeffb3af00adb22c6bb"
+        );
+        assert_eq!(
+            hex::encode(encode(&G2Affine::generator()).unwrap()),
+            "93e02b6052719f607dacd3a088274f65596bd0d09920b61ab5da61bbdc7f5049334cf11213945d57e5ac7d055d042b7e024aa2b2f08f0a91260805272dc51051c6e47ad4fa403b02b4510b647ae3d1770bac0326a805bbefd48056c8c121bdb8"
+        );
+
+        let mut rng = test_rng();
+        let sk = Fr::rand(&mut rng);
+        let suite = Ciphersuite::<MinSig>::new(Scheme::Basic);
+        let pk = Ciphersuite::<MinSig>::sk_to_pk(&sk);
+        let sig = suite.sign(&sk, b"message").unwrap();
+        let pk_bytes = encode(&pk).unwrap();
+        let sig_bytes = encode(&sig).unwrap();
+        assert_eq!((pk_bytes.len(), sig_bytes.len()), (96, 48));
+        assert_eq!(
+            Ciphersuite::<MinSig>::decode_public_key(&pk_bytes).unwrap(),
+            pk
+        );
+        assert_eq!(
+            Ciphersuite::<MinSig>::decode_signature(&sig_bytes).unwrap(),
+            sig
+        );
+    }
+
+    #[test]
+    fn test_schemes_and_variants() {
+        let mut rng = test_rng();
+        let sk = key_gen(&Fr::rand(&mut rng).into_bigint().to_bytes_be(), b"").unwrap();
+        let message = b"hello BLS";
+
+        fn check<V: Variant>(sk: &Fr, message: &[u8]) {
+            let pk = Ciphersuite::<V>::sk_to_pk(sk);
+            let signatures: Vec<_> = [
+                Scheme::Basic,
+                Scheme::MessageAugmentation,
+                Scheme::ProofOfPossession,
+            ]
+            .into_iter()
+            .map(|scheme| {
+                let suite = Ciphersuite::<V>::new(scheme);
+                let sig = suite.sign(sk, message).unwrap();
+                assert!(suite.verify(&pk, message, &sig).is_ok());
+                sig
+            })
+            .collect();
+
+            // every scheme has its own tag, so signatures do not carry over
+            assert_ne!(signatures[0], signatures[1]);
+            assert_ne!(signatures[0], signatures[2]);
+            let basic = Ciphersuite::<V>::new(Scheme::Basic);
+            assert!(basic.verify(&pk, message, &signatures[2]).is_err());
+        }
+
+        check::<MinPk>(&sk, message);
+        check::<MinSig>(&sk, message);
+    }
+
+    #[test]
+    fn test_proof_of_possession() {
+        let mut rng = test_rng();
+        let suite = Ciphersuite::<MinPk>::new(Scheme::ProofOfPossession);
+        let sk = Fr::rand(&mut rng);
+        let pk = Ciphersuite::<MinPk>::sk_to_pk(&sk);
+        let proof = suite.pop_prove(&sk).unwrap();
+        assert!(suite.pop_verify(&pk, &proof).is_ok());
+
+        // a proof is not a signature on the public key, and does not prove another key
+        let pk_bytes = encode(&pk).unwrap();
+        assert!(suite.verify(&pk, &pk_bytes, &proof).is_err());
+        let other = Ciphersuite::<MinPk>::sk_to_pk(&Fr::rand(&mut rng));
+        assert!(matches!(
+            suite.pop_verify(&other, &proof),
+            Err(MPCError::VerificationFailed)
+        ));
+
+        assert!(matches!(
+            suite.pop_verify(&G1Affine::zero(), &proof),
+            Err(MPCError::InvalidPublicKey)
+        ));
+
+        // the other schemes do not define proofs of possession
+        for scheme in [Scheme::Basic, Scheme::MessageAugmentation] {
+            let suite = Ciphersuite::<MinPk>::new(scheme);
+            assert!(matches!(
+                suite.pop_prove(&sk),
+                Err(MPCError::UnsupportedScheme(s)) if s == scheme
+            ));
+            assert!(matches!(
+                suite.pop_verify(&pk, &proof),
+                Err(MPCError::UnsupportedScheme(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_threshold_signatures_are_min_sig() {
+        let mut rng = test_rng();
+        let (wallet, shares) = MPCWallet::keygen(2, 3, &mut rng).unwrap();
+        let message = b"threshold";
+        let signature = wallet
+            .combine_signature_shares(
+                message,
+                &[
+                    MPCWallet::sign_share(message, &shares[0]),
+                    MPCWallet::sign_share(message, &shares[2]),
+                ],
+            )
+            .unwrap();
+
+        let suite = Ciphersuite::<MinSig>::new(Scheme::Basic);
+        assert!(suite
+            .verify(&wallet.public_key, message, &signature)
+            .is_ok());
+    }
+}
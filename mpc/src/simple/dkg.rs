@@ -0,0 +1,730 @@
+use std::collections::{btree_map::Entry, BTreeMap, BTreeSet};
+
+use ark_bls12_381::{Fr, G2Affine, G2Projective};
+use ark_ec::{CurveGroup, PrimeGroup};
+use ark_ff::{One, Zero};
+use ark_std::{rand::RngCore, UniformRand};
+
+use super::KeyShare;
+use super::MPCError;
+use super::MPCWallet;
+
+/// A message of the distributed key generation
+///
+/// `Share` goes to one participant over a private channel, everything else is broadcast.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DkgMessage {
+    /// Feldman commitments A_k = g2^{a_k} to the dealer's coefficients
+    Commitments {
+        from: u32,
+        commitments: Vec<G2Affine>,
+    },
+    /// f_from(to), for `to` only
+    Share { from: u32, to: u32, share: Fr },
+    /// `from` got no share from `against`, or one that does not match the commitments
+    Complaint { from: u32, against: u32 },
+    /// The dealer answers a complaint by publishing the share it owes `to`
+    Justification { from: u32, to: u32, share: Fr },
+}
+
+impl DkgMessage {
+    /// The participant a private message is for, `None` for broadcasts
+    pub fn recipient(&self) -> Option<u32> {
+        match self {
+            DkgMessage::Share { to, .. } => Some(*to),
+            _ => None,
+        }
+    }
+
+    fn sender(&self) -> u32 {
+        match self {
+            DkgMessage::Commitments { from, .. }
+            | DkgMessage::Share { from, .. }
+            | DkgMessage::Complaint { from, .. }
+            | DkgMessage::Justification { from, .. } => *from,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum Phase {
+    Start,
+    Dealt,
+    Complained,
+    Justified,
+}
+
+/// One participant of a dealerless key generation (Pedersen's joint Feldman DKG, with the
+/// complaint round of Gennaro, Jarecki, Krawczyk and Rabin)
+///
+/// 1. Every participant i deals a random polynomial f_i of degree t - 1: it broadcasts
+///    Feldman commitments A_ik = g2^{a_ik} and sends f_i(j) privately to every j.
+/// 2. Participant j checks each share: g2^{f_i(j)} = Π(A_ik^{j^k}), and broadcasts a complaint
+///    against every dealer whose share is missing or wrong.
+/// 3. A dealer answers each complaint against it by broadcasting the share it owes.
+/// 4. A dealer is disqualified if it broadcast malformed commitments, left a complaint unanswered,
+///    or answered one with a share that does not match its commitments. The others form QUAL,
+///    and participant j ends up with
+///
+///    x_j = Σ(f_i(j)) for i in QUAL,  public key = Σ(A_i0) for i in QUAL
+///
+/// The group secret key Σ(f_i(0)) is never held by anyone: every participant only learns its own
+/// point on the sum polynomial. As GJKR point out, a rushing adversary can still bias the public
+/// key by choosing whom to get disqualified, which is acceptable for threshold BLS signatures.
+///
+/// Rounds are driven by the caller: deliver all messages of a round with `handle`, then call
+/// `advance` on every participant to get the messages of the next round.
+#[derive(Debug)]
+pub struct DkgParticipant {
+    index: u32,
+    threshold: usize,
+    total_participants: usize,
+    phase: Phase,
+    coefficients: Vec<Fr>,
+    commitments: BTreeMap<u32, Vec<G2Affine>>,
+    shares: BTreeMap<u32, Fr>,
+    complaints: BTreeSet<(u32, u32)>,
+    justifications: BTreeMap<(u32, u32), Fr>,
+}
+
+impl DkgParticipant {
+    /// Participant `index` in 1..=total_participants, sampling its polynomial from `rng`
+    pub fn new<R: RngCore>(
+        index: u32,
+        threshold: usize,
+        total_participants: usize,
+        rng: &mut R,
+    ) -> Result<Self, MPCError> {
+        if total_participants < 2 {
+            return Err(MPCError::InvalidParticipants(total_participants));
+        }
+        if threshold > total_participants || threshold < 1 {
+            return Err(MPCError::InvalidThreshold(threshold));
+        }
+        if index < 1 || index as usize > total_participants {
+            return Err(MPCError::InvalidParticipants(index as usize));
+        }
+
+        Ok(Self {
+            index,
+            threshold,
+            total_participants,
+            phase: Phase::Start,
+            coefficients: (0..threshold).map(|_| Fr::rand(rng)).collect(),
+            commitments: BTreeMap::new(),
+            shares: BTreeMap::new(),
+            complaints: BTreeSet::new(),
+            justifications: BTreeMap::new(),
+        })
+    }
+
+    pub fn index(&self) -> u32 {
+        self.index
+    }
+
+    /// Stores a message addressed to this participant, from the current or an earlier round
+    pub fn handle(&mut self, message: DkgMessage) -> Result<(), MPCError> {
+        let from = message.sender();
+        if from == self.index || !self.is_participant(from) {
+            return Err(MPCError::UnexpectedMessage(format!(
+                "message from participant {from}"
+            )));
+        }
+
+        let fresh = match message {
+            DkgMessage::Commitments { commitments, .. } if self.phase <= Phase::Dealt => {
+                insert_once(&mut self.commitments, from, commitments)
+            }
+            DkgMessage::Share { to, share, .. } if self.phase <= Phase::Dealt => {
+                if to != self.index {
+                    return Err(MPCError::UnexpectedMessage(format!(
+                        "share for participant {to}"
+                    )));
+                }
+                insert_once(&mut self.shares, from, share)
+            }
+            DkgMessage::Complaint { against, .. } if self.phase <= Phase::Complained => {
+                if !self.is_participant(against) {
+                    return Err(MPCError::UnexpectedMessage(format!(
+                        "complaint against participant {against}"
+                    )));
+                }
+                self.complaints.insert((from, against))
+            }
+            DkgMessage::Justification { to, share, .. } if self.phase <= Phase::Justified => {
+                if !self.is_participant(to) {
+                    return Err(MPCError::UnexpectedMessage(format!(
+                        "justification for participant {to}"
+                    )));
+                }
+                // a dealer only publishes the shares it was asked for
+                if !self.complaints.contains(&(to, from)) {
+                    return Err(MPCError::UnexpectedMessage(format!(
+                        "justification for participant {to}, who did not complain"
+                    )));
+                }
+                insert_once(&mut self.justifications, (from, to), share)
+            }
+            message => {
+                return Err(MPCError::UnexpectedMessage(format!(
+                    "{message:?} after its round"
+                )))
+            }
+        };
+
+        if !fresh {
+            return Err(MPCError::UnexpectedMessage(format!(
+                "repeated message from participant {from}"
+            )));
+        }
+        Ok(())
+    }
+
+    /// Ends the current round and returns the messages of the next one
+    pub fn advance(&mut self) -> Result<Vec<DkgMessage>, MPCError> {
+        let messages = match self.phase {
+            Phase::Start => self.deal(),
+            Phase::Dealt => self.complain(),
+            Phase::Complained => self.justify(),
+            Phase::Justified => {
+                return Err(MPCError::UnexpectedMessage(
+                    "the key generation is over".to_string(),
+                ))
+            }
+        };
+        self.phase = match self.phase {
+            Phase::Start => Phase::Dealt,
+            Phase::Dealt => Phase::Complained,
+            _ => Phase::Justified,
+        };
+        Ok(messages)
+    }
+
+    /// The wallet and this participant's key share, once the last round is over
+    pub fn finish(&self) -> Result<(MPCWallet, KeyShare), MPCError> {
+        if self.phase != Phase::Justified {
+            return Err(MPCError::UnexpectedMessage(
+                "the key generation is not over".to_string(),
+            ));
+        }
+
+        let qualified = self.qualified();
+        if qualified.is_empty() {
+            return Err(MPCError::NoQualifiedDealers);
+        }
+
+        let mut secret_share = Fr::zero();
+        let mut public_key = G2Projective::zero();
+        for &dealer in &qualified {
+            secret_share += self
+                .share_from(dealer)
+                .expect("qualified dealers have shares");
+            public_key += self.commitments[&dealer][0];
+        }
+
+        let key_share = KeyShare {
+            index: self.index,
+            secret_share,
+            public_key_share: (G2Projective::generator() * secret_share).into_affine(),
+        };
+        let public_key_shares = (1..=self.total_participants as u32)
+            .map(|index| {
+                self.public_key_share(index)
+                    .expect("the key generation is over")
+            })
+            .collect();
+        let wallet = MPCWallet {
+            threshold: self.threshold,
+            total_participants: self.total_participants,
+            public_key: public_key.into_affine(),
+            public_key_shares,
+        };
+        Ok((wallet, key_share))
+    }
+
+    /// g2^{x_j} for any participant j, computed from the public commitments of QUAL alone
+    pub fn public_key_share(&self, index: u32) -> Option<G2Affine> {
+        if self.phase != Phase::Justified || !self.is_participant(index) {
+            return None;
+        }
+        let qualified = self.qualified();
+        let share = qualified
+            .iter()
+            .map(|dealer| evaluate_commitments(&self.commitments[dealer], index))
+            .sum::<G2Projective>();
+        Some(share.into_affine())
+    }
+
+    fn deal(&mut self) -> Vec<DkgMessage> {
+        let commitments: Vec<G2Affine> = self
+            .coefficients
+            .iter()
+            .map(|&coefficient| (G2Projective::generator() * coefficient).into_affine())
+            .collect();
+        self.commitments.insert(self.index, commitments.clone());
+
+        let mut messages = vec![DkgMessage::Commitments {
+            from: self.index,
+            commitments,
+        }];
+        for to in 1..=self.total_participants as u32 {
+            let share = evaluate_polynomial(&self.coefficients, to);
+            if to == self.index {
+                self.shares.insert(to, share);
+            } else {
+                messages.push(DkgMessage::Share {
+                    from: self.index,
+                    to,
+                    share,
+                });
+            }
+        }
+        messages
+    }
+
+    fn complain(&mut self) -> Vec<DkgMessage> {
+        let mut messages = Vec::new();
+        for dealer in self.participants() {
+            let valid = match (self.commitments.get(&dealer), self.shares.get(&dealer)) {
+                (Some(commitments), Some(&share)) => self.verify(commitments, self.index, share),
+                _ => false,
+            };
+            if !valid {
+                self.complaints.insert((self.index, dealer));
+                messages.push(DkgMessage::Complaint {
+                    from: self.index,
+                    against: dealer,
+                });
+            }
+        }
+        messages
+    }
+
+    fn justify(&mut self) -> Vec<DkgMessage> {
+        let complainers: Vec<u32> = self
+            .complaints
+            .iter()
+            .filter(|&&(_, against)| against == self.index)
+            .map(|&(from, _)| from)
+            .collect();
+
+        complainers
+            .into_iter()
+            .map(|to| {
+                let share = evaluate_polynomial(&self.coefficients, to);
+                self.justifications.insert((self.index, to), share);
+                DkgMessage::Justification {
+                    from: self.index,
+                    to,
+                    share,
+                }
+            })
+            .collect()
+    }
+
+    /// The dealers that were not disqualified, the same at every honest participant since
+    /// it only depends on broadcast messages
+    fn qualified(&self) -> Vec<u32> {
+        self.participants()
+            .filter(|&dealer| {
+                let Some(commitments) = self.commitments.get(&dealer) else {
+                    return false;
+                };
+                if commitments.len() != self.threshold {
+                    return false;
+                }
+                self.complaints
+                    .iter()
+                    .filter(|&&(_, against)| against == dealer)
+                    .all(
+                        |&(from, _)| match self.justifications.get(&(dealer, from)) {
+                            Some(&share) => self.verify(commitments, from, share),
+                            None => false,
+                        },
+                    )
+            })
+            .collect()
+    }
+
+    /// The share this participant got from `dealer`, or, if it complained, the one the dealer
+    /// published in answer and that matches the commitments
+    fn share_from(&self, dealer: u32) -> Option<Fr> {
+        if !self.complaints.contains(&(self.index, dealer)) {
+            return self.shares.get(&dealer).copied();
+        }
+        let commitments = self.commitments.get(&dealer)?;
+        self.justifications
+            .get(&(dealer, self.index))
+            .copied()
+            .filter(|&share| self.verify(commitments, self.index, share))
+    }
+
+    fn verify(&self, commitments: &[G2Affine], index: u32, share: Fr) -> bool {
+        commitments.len() == self.threshold
+            && G2Projective::generator() * share == evaluate_commitments(commitments, index)
+    }
+
+    fn participants(&self) -> impl Iterator<Item = u32> {
+        1..=self.total_participants as u32
+    }
+
+    fn is_participant(&self, index: u32) -> bool {
+        index >= 1 && index as usize <= self.total_participants
+    }
+}
+
+/// Runs a complete key generation among `total_participants` honest participants in memory,
+/// the dealerless counterpart of `MPCWallet::keygen`
+pub fn run_dkg<R: RngCore>(
+    threshold: usize,
+    total_participants: usize,
+    rng: &mut R,
+) -> Result<(MPCWallet, Vec<KeyShare>), MPCError> {
+    let mut participants = (1..=total_participants as u32)
+        .map(|index| DkgParticipant::new(index, threshold, total_participants, rng))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    for _ in 0..3 {
+        let mut messages = Vec::new();
+        for participant in &mut participants {
+            messages.extend(participant.advance()?);
+        }
+        deliver(&mut participants, messages)?;
+    }
+
+    let mut wallet = None;
+    let mut shares = Vec::with_capacity(total_participants);
+    for participant in &participants {
+        let (participant_wallet, share) = participant.finish()?;
+        wallet = Some(participant_wallet);
+        shares.push(share);
+    }
+    Ok((wallet.expect("at least two participants"), shares))
+}
+
+/// Hands every message to its recipient, or to everyone but the sender if it is broadcast
+fn deliver(participants: &mut [DkgParticipant], messages: Vec<DkgMessage>) -> Result<(), MPCError> {
+    for message in messages {
+        for participant in participants.iter_mut() {
+            let addressed = match message.recipient() {
+                Some(to) => to == participant.index,
+                None => message.sender() != participant.index,
+            };
+            if addressed {
+                participant.handle(message.clone())?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Stores `value` unless `key` already has one, which is then kept
+fn insert_once<K: Ord, V>(map: &mut BTreeMap<K, V>, key: K, value: V) -> bool {
+    match map.entry(key) {
+        Entry::Vacant(entry) => {
+            entry.insert(value);
+            true
+        }
+        Entry::Occupied(_) => false,
+    }
+}
+
+/// f(x) = Σ(a_k * x^k)
+fn evaluate_polynomial(coefficients: &[Fr], index: u32) -> Fr {
+    let x = Fr::from(index);
+    coefficients
+        .iter()
+        .rev()
+        .fold(Fr::zero(), |acc, &coefficient| acc * x + coefficient)
+}
+
+/// g2^{f(x)} = Π(A_k^{x^k})
+fn evaluate_commitments(commitments: &[G2Affine], index: u32) -> G2Projective {
+    let x = Fr::from(index);
+    let mut power = Fr::one();
+    let mut result = G2Projective::zero();
+    for commitment in commitments {
+        result += *commitment * power;
+        power *= x;
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_std::test_rng;
+
+    use super::*;
+
+    /// Runs the rounds, letting `tamper` rewrite or drop (by returning false) every message
+    fn run_with(
+        participants: &mut [DkgParticipant],
+        mut tamper: impl FnMut(&mut DkgMessage) -> bool,
+    ) {
+        for _ in 0..3 {
+            let mut messages = Vec::new();
+            for participant in participants.iter_mut() {
+                messages.extend(participant.advance().unwrap());
+            }
+            messages.retain_mut(|message| tamper(message));
+            deliver(participants, messages).unwrap();
+        }
+    }
+
+    fn participants(threshold: usize, total: usize) -> Vec<DkgParticipant> {
+        let mut rng = test_rng();
+        (1..=total as u32)
+            .map(|index| DkgParticipant::new(index, threshold, total, &mut rng).unwrap())
+            .collect()
+    }
+
+    fn secret_from(shares: &[KeyShare]) -> Fr {
+        // Lagrange interpolation at 0
+        shares.iter().fold(Fr::zero(), |acc, share| {
+            let xi = Fr::from(share.index);
+            let lambda = shares
+                .iter()
+                .filter(|other| other.index != share.index)
+                .fold(Fr::one(), |acc, other| {
+                    let xj = Fr::from(other.index);
+                    acc * xj / (xj - xi)
+                });
+            acc + share.secret_share * lambda
+        })
+    }
+
+    #[test]
+    fn test_dkg_produces_a_working_wallet() {
+        let mut rng = test_rng();
+        let (wallet, shares) = run_dkg(3, 5, &mut rng).unwrap();
+        assert_eq!(wallet.threshold, 3);
+        assert_eq!(shares.len(), 5);
+        for share in &shares {
+            assert_eq!(
+                wallet.public_key_shares[share.index as usize - 1],
+                share.public_key_share
+            );
+        }
+
+        let message = b"Send 1 BTC to Alice";
+        let signature_shares: Vec<_> = [0, 2, 4]
+            .iter()
+            .map(|&i| MPCWallet::sign_share(message, &shares[i]))
+            .collect();
+        let signature = wallet
+            .combine_signature_shares(message, &signature_shares)
+            .unwrap();
+        assert!(MPCWallet::verify(&wallet.public_key, message, &signature).is_ok());
+
+        // any t shares agree on the key, which nobody held during the protocol
+        let secret = secret_from(&shares[..3]);
+        assert_eq!(secret, secret_from(&shares[2..]));
+        assert_eq!(
+            (G2Projective::generator() * secret).into_affine(),
+            wallet.public_key
+        );
+    }
+
+    #[test]
+    fn test_participants_agree() {
+        let mut participants = participants(2, 4);
+        run_with(&mut participants, |_| true);
+
+        let (wallet, _) = participants[0].finish().unwrap();
+        for participant in &participants {
+            let (other, share) = participant.finish().unwrap();
+            assert_eq!(other.public_key, wallet.public_key);
+            // everyone can compute everyone's public key share from the commitments
+            assert_eq!(
+                participants[0].public_key_share(share.index),
+                Some(share.public_key_share)
+            );
+        }
+    }
+
+    #[test]
+    fn test_justified_complaint_keeps_the_dealer() {
+        let mut participants = participants(2, 4);
+        // dealer 1's share to participant 3 gets lost, so 3 complains and 1 publishes it
+        run_with(&mut participants, |message| {
+            !matches!(message, DkgMessage::Share { from: 1, to: 3, .. })
+        });
+
+        assert_eq!(participants[3].qualified(), [1, 2, 3, 4]);
+        let shares: Vec<KeyShare> = participants
+            .iter()
+            .map(|participant| participant.finish().unwrap().1)
+            .collect();
+        assert_eq!(secret_from(&shares[..2]), secret_from(&shares[2..]));
+    }
+
+    #[test]
+    fn test_cheating_dealer_is_disqualified() {
+        let mut participants = participants(3, 5);
+
+        // dealer 2 sends a bad share to 4, then publishes a bad one again
+        run_with(&mut participants, |message| {
+            match message {
+                DkgMessage::Share {
+                    from: 2,
+                    to: 4,
+                    share,
+                }
+                | DkgMessage::Justification {
+                    from: 2,
+                    to: 4,
+                    share,
+                } => *share += Fr::one(),
+                _ => {}
+            }
+            true
+        });
+
+        let outputs: Vec<_> = participants
+            .iter()
+            .map(|participant| participant.finish().unwrap())
+            .collect();
+        // every honest participant drops dealer 2
+        for participant in participants.iter().filter(|p| p.index() != 2) {
+            assert_eq!(participant.qualified(), [1, 3, 4, 5]);
+        }
+        let shares: Vec<KeyShare> = outputs
+            .iter()
+            .map(|(_, share)| share.clone())
+            .filter(|share| share.index != 2)
+            .collect();
+
+        // the key is the sum of the qualified dealers' secrets, here known to the test only
+        let qualified_secret: Fr = [0, 2, 3, 4]
+            .iter()
+            .map(|&i| participants[i].coefficients[0])
+            .sum();
+        assert_eq!(secret_from(&shares[..3]), qualified_secret);
+        assert_eq!(secret_from(&shares[1..]), qualified_secret);
+        assert_eq!(
+            (G2Projective::generator() * qualified_secret).into_affine(),
+            outputs[0].0.public_key
+        );
+    }
+
+    #[test]
+    fn test_unsolicited_justification_is_ignored() {
+        let mut participants = participants(2, 4);
+        run_with(&mut participants, |_| true);
+
+        // nobody complained about dealer 1, so it has no business publishing a share for 3
+        let (_, before) = participants[2].finish().unwrap();
+        for participant in participants.iter_mut().filter(|p| p.index() != 1) {
+            assert!(matches!(
+                participant.handle(DkgMessage::Justification {
+                    from: 1,
+                    to: 3,
+                    share: Fr::one(),
+                }),
+                Err(MPCError::UnexpectedMessage(_))
+            ));
+        }
+
+        let (wallet, share) = participants[2].finish().unwrap();
+        assert_eq!(share, before);
+        assert_eq!(wallet.public_key_shares[2], share.public_key_share);
+        assert_eq!(participants[2].qualified(), [1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn test_unanswered_complaint_disqualifies() {
+        let mut participants = participants(2, 3);
+        run_with(&mut participants, |message| {
+            !matches!(
+                message,
+                DkgMessage::Share { from: 3, to: 1, .. }
+                    | DkgMessage::Justification { from: 3, .. }
+            )
+        });
+        assert_eq!(participants[0].qualified(), [1, 2]);
+        assert_eq!(participants[1].qualified(), [1, 2]);
+    }
+
+    #[test]
+    fn test_out_of_order_messages_are_rejected() {
+        let mut participants = participants(2, 3);
+        let messages = participants[0].advance().unwrap();
+        let share = messages
+            .iter()
+            .find(|message| message.recipient() == Some(2))
+            .unwrap()
+            .clone();
+
+        let commitments = messages[0].clone();
+        participants[1].handle(share.clone()).unwrap();
+        participants[1].handle(commitments.clone()).unwrap();
+        assert!(matches!(
+            participants[1].handle(share.clone()),
+            Err(MPCError::UnexpectedMessage(_))
+        ));
+
+        // a repeat with another value is rejected and leaves the first one in place
+        let DkgMessage::Share { share: first, .. } = share else {
+            unreachable!()
+        };
+        let DkgMessage::Commitments {
+            commitments: first_commitments,
+            ..
+        } = commitments
+        else {
+            unreachable!()
+        };
+        for message in [
+            DkgMessage::Share {
+                from: 1,
+                to: 2,
+                share: first + Fr::one(),
+            },
+            DkgMessage::Commitments {
+                from: 1,
+                commitments: first_commitments.iter().rev().copied().collect(),
+            },
+        ] {
+            assert!(matches!(
+                participants[1].handle(message),
+                Err(MPCError::UnexpectedMessage(_))
+            ));
+        }
+        assert_eq!(participants[1].shares[&1], first);
+        assert_eq!(participants[1].commitments[&1], first_commitments);
+        assert!(matches!(
+            participants[2].handle(DkgMessage::Share {
+                from: 1,
+                to: 2,
+                share: first,
+            }),
+            Err(MPCError::UnexpectedMessage(_))
+        ));
+        assert!(matches!(
+            participants[1].finish(),
+            Err(MPCError::UnexpectedMessage(_))
+        ));
+        assert!(matches!(
+            DkgParticipant::new(4, 2, 3, &mut test_rng()),
+            Err(MPCError::InvalidParticipants(4))
+        ));
+
+        // complaints and justifications must name participants too
+        for message in [
+            DkgMessage::Complaint {
+                from: 1,
+                against: 4,
+            },
+            DkgMessage::Justification {
+                from: 1,
+                to: 0,
+                share: Fr::one(),
+            },
+        ] {
+            assert!(matches!(
+                participants[1].handle(message),
+                Err(MPCError::UnexpectedMessage(_))
+            ));
+        }
+    }
+}
@@ -1,5 +1,7 @@
 use thiserror::Error;
 
+use super::Scheme;
+
 #[derive(Debug, Error)]
 pub enum MPCError {
     #[error("Invalid number of participants: {0}")]
@@ -19,4 +21,25 @@ pub enum MPCError {
 
     #[error("Verification failed")]
     VerificationFailed,
+
+    #[error("Unexpected key generation message: {0}")]
+    UnexpectedMessage(String),
+
+    #[error("Every dealer was disqualified")]
+    NoQualifiedDealers,
+
+    #[error("Hash to curve failed: {0}")]
+    HashToCurve(String),
+
+    #[error("Key material too short: {0} bytes, at least 32 are needed")]
+    InvalidKeyMaterial(usize),
+
+    #[error("Invalid public key")]
+    InvalidPublicKey,
+
+    #[error("Not supported by the {0:?} scheme")]
+    UnsupportedScheme(Scheme),
+
+    #[error("Not enough valid signature shares, invalid shares from participants {0:?}")]
+    InvalidSignatureShares(Vec<u32>),
 }
@@ -0,0 +1,179 @@
+use ark_bls12_381::{g1, g2, G1Affine, G2Affine};
+use ark_ec::hashing::{
+    curve_maps::wb::{WBConfig, WBMap},
+    map_to_curve_hasher::MapToCurveBasedHasher,
+    HashToCurve,
+};
+use ark_ec::short_weierstrass::{Affine, Projective};
+use ark_ff::field_hashers::{DefaultFieldHasher, HashToField};
+use sha2::Sha256;
+
+use super::MPCError;
+
+/// Domain separation tag of the IETF BLS signature ciphersuite with signatures on G1
+/// (draft-irtf-cfrg-bls-signature, basic scheme), used by `MPCWallet::sign_share` and `MPCWallet::verify`
+pub const DEFAULT_DST: &[u8] = b"BLS_SIG_BLS12381G1_XMD:SHA-256_SSWU_RO_NUL_";
+
+/// ```md
+/// RFC 9380 hash_to_curve with the BLS12381G1_XMD:SHA-256_SSWU_RO_ suite
+///
+/// 1. (u_0, u_1) = hash_to_field(msg, 2), with expand_message_xmd and SHA-256
+/// 2. Q_i = iso_map(map_to_curve_simple_swu(u_i)), SSWU on a curve 11-isogenous to G1
+/// 3. P = clear_cofactor(Q_0 + Q_1)
+///
+/// Nobody knows the discrete log of P, unlike with P = g * H(msg): there, sig = P * sk
+/// for one message gives sig * (H(msg') / H(msg)) as a valid signature on any other msg'.
+/// ```
+///
+/// `dst` separates the uses of the hash: every protocol (and every ciphersuite) needs its own.
+/// Tags over 255 bytes are hashed down first, as the RFC specifies.
+pub fn hash_to_curve(message: &[u8], dst: &[u8]) -> Result<G1Affine, MPCError> {
+    hash_with::<g1::Config>(message, dst)
+}
+
+/// RFC 9380 hash_to_curve with the BLS12381G2_XMD:SHA-256_SSWU_RO_ suite: the same steps as
+/// `hash_to_curve` over Fq2, with a 3-isogeny, for ciphersuites with signatures on G2
+pub fn hash_to_g2(message: &[u8], dst: &[u8]) -> Result<G2Affine, MPCError> {
+    hash_with::<g2::Config>(message, dst)
+}
+
+fn hash_with<C: WBConfig>(message: &[u8], dst: &[u8]) -> Result<Affine<C>, MPCError>
+where
+    DefaultFieldHasher<Sha256, 128>: HashToField<C::BaseField>,
+{
+    if dst.is_empty() {
+        return Err(MPCError::HashToCurve(
+            "empty domain separation tag".to_string(),
+        ));
+    }
+
+    let hasher =
+        MapToCurveBasedHasher::<Projective<C>, DefaultFieldHasher<Sha256, 128>, WBMap<C>>::new(dst)
+            .map_err(|e| MPCError::HashToCurve(e.to_string()))?;
+    hasher
+        .hash(message)
+        .map_err(|e| MPCError::HashToCurve(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_bls12_381::{Fq, Fq2};
+    use ark_ec::AffineRepr;
+    use ark_ff::PrimeField;
+
+    use super::*;
+
+    const RFC_DST: &[u8] = b"QUUX-V01-CS02-with-BLS12381G1_XMD:SHA-256_SSWU_RO_";
+
+    fn fq(hex: &str) -> Fq {
+        Fq::from_be_bytes_mod_order(&hex::decode(hex).unwrap())
+    }
+
+    /// RFC 9380, appendix J.9.1
+    #[test]
+    fn test_rfc_vectors() {
+        let vectors = [
+            (
+                "".to_string(),
+                "052926add2207b76ca4fa57a8734416c8dc95e24501772c814278700eed6d1e4e8cf62d9c09db0fac349612b759e79a1",
+                "08ba738453bfed09cb546dbb0783dbb3a5f1f566ed67bb6be0e8c67e2e81a4cc68ee29813bb7994998f3eae0c9c6a265",
+            ),
+            (
+                "abc".to_string(),
+                "03567bc5ef9c690c2ab2ecdf6a96ef1c139cc0b2f284dca0a9a7943388a49a3aee664ba5379a7655d3c68900be2f6903",
+                "0b9c15f3fe6e5cf4211f346271d7b01c8f3b28be689c8429c85b67af215533311f0b8dfaaa154fa6b88176c229f2885d",
+            ),
+            (
+                "abcdef0123456789".to_string(),
+                "11e0b079dea29a68f0383ee94fed1b940995272407e3bb916bbf268c263ddd57a6a27200a784cbc248e84f357ce82d98",
+                "03a87ae2caf14e8ee52e51fa2ed8eefe80f02457004ba4d486d6aa1f517c0889501dc7413753f9599b099ebcbbd2d709",
+            ),
+            (
+                format!("q128_{}", "q".repeat(128)),
+                "15f68eaa693b95ccb85215dc65fa81038d69629f70aeee0d0f677cf22285e7bf58d7cb86eefe8f2e9bc3f8cb84fac488",
+                "1807a1d50c29f430b8cafc4f8638dfeeadf51211e1602a5f184443076715f91bb90a48ba1e370edce6ae1062f5e6dd38",
+            ),
+            (
+                format!("a512_{}", "a".repeat(512)),
+                "082aabae8b7dedb0e78aeb619ad3bfd9277a2f77ba7fad20ef6aabdc6c31d19ba5a6d12283553294c1825c4b3ca2dcfe",
+                "05b84ae5a942248eea39e1d91030458c40153f3b654ab7872d779ad1e942856a20c438e8d99bc8abfbf74729ce1f7ac8",
+            ),
+        ];
+
+        for (message, x, y) in vectors {
+            let point = hash_to_curve(message.as_bytes(), RFC_DST).unwrap();
+            assert_eq!(point, G1Affine::new(fq(x), fq(y)), "msg = {message:?}");
+        }
+    }
+
+    /// RFC 9380, appendix J.10.1
+    #[test]
+    fn test_rfc_vectors_g2() {
+        let dst = b"QUUX-V01-CS02-with-BLS12381G2_XMD:SHA-256_SSWU_RO_";
+        let fq2 = |c0, c1| Fq2::new(fq(c0), fq(c1));
+        let vectors = [
+            (
+                "",
+                [
+                    "0141ebfbdca40eb85b87142e130ab689c673cf60f1a3e98d69335266f30d9b8d4ac44c1038e9dcdd5393faf5c41fb78a",
+                    "05cb8437535e20ecffaef7752baddf98034139c38452458baeefab379ba13dff5bf5dd71b72418717047f5b0f37da03d",
+                    "0503921d7f6a12805e72940b963c0cf3471c7b2a524950ca195d11062ee75ec076daf2d4bc358c4b190c0c98064fdd92",
+                    "12424ac32561493f3fe3c260708a12b7c620e7be00099a974e259ddc7d1f6395c3c811cdd19f1e8dbf3e9ecfdcbab8d6",
+                ],
+            ),
+            (
+                "abc",
+                [
+                    "02c2d18e033b960562aae3cab37a27ce00d80ccd5ba4b7fe0e7a210245129dbec7780ccc7954725f4168aff2787776e6",
+                    "139cddbccdc5e91b9623efd38c49f81a6f83f175e80b06fc374de9eb4b41dfe4ca3a230ed250fbe3a2acf73a41177fd8",
+                    "1787327b68159716a37440985269cf584bcb1e621d3a7202be6ea05c4cfe244aeb197642555a0645fb87bf7466b2ba48",
+                    "00aa65dae3c8d732d10ecd2c50f8a1baf3001578f71c694e03866e9f3d49ac1e1ce70dd94a733534f106d4cec0eddd16",
+                ],
+            ),
+        ];
+
+        for (message, [x0, x1, y0, y1]) in vectors {
+            let point = hash_to_g2(message.as_bytes(), dst).unwrap();
+            assert_eq!(
+                point,
+                G2Affine::new(fq2(x0, x1), fq2(y0, y1)),
+                "msg = {message:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_points_are_in_the_subgroup() {
+        for message in [&b""[..], b"hello world", &[0xff; 1000]] {
+            let point = hash_to_curve(message, DEFAULT_DST).unwrap();
+            assert!(point.is_on_curve());
+            assert!(point.is_in_correct_subgroup_assuming_on_curve());
+            assert!(!point.is_zero());
+        }
+    }
+
+    #[test]
+    fn test_domain_separation() {
+        let message = b"hello world";
+        let default = hash_to_curve(message, DEFAULT_DST).unwrap();
+        assert_ne!(default, hash_to_curve(message, RFC_DST).unwrap());
+        assert_ne!(
+            default,
+            hash_to_curve(b"hello world!", DEFAULT_DST).unwrap()
+        );
+
+        // long tags are hashed down to H2C-OVERSIZE-DST- || H(dst), and still separate
+        let long = [b'x'; 300];
+        let mut longer = long.to_vec();
+        longer.push(b'x');
+        assert_ne!(
+            hash_to_curve(message, &long).unwrap(),
+            hash_to_curve(message, &longer).unwrap()
+        );
+
+        assert!(matches!(
+            hash_to_curve(message, b""),
+            Err(MPCError::HashToCurve(_))
+        ));
+    }
+}
@@ -1,9 +1,15 @@
+mod ciphersuite;
+mod dkg;
 mod errors;
+mod hash;
 mod key;
 mod sig;
 mod wallet;
 
+pub use ciphersuite::*;
+pub use dkg::*;
 pub use errors::*;
+pub use hash::*;
 pub use key::*;
 pub use sig::*;
 pub use wallet::*;
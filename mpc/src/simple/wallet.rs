@@ -3,17 +3,19 @@ use std::collections::HashMap;
 use super::KeyShare;
 use super::MPCError;
 use super::SignatureShare;
+use super::{hash_to_curve, DEFAULT_DST};
 use ark_bls12_381::{Bls12_381, Fr, G1Affine, G1Projective, G2Affine, G2Projective};
 use ark_ec::{pairing::Pairing, AffineRepr, CurveGroup, PrimeGroup};
-use ark_ff::{Field, One, PrimeField, Zero};
+use ark_ff::{Field, One, Zero};
 use ark_std::{rand::RngCore, UniformRand};
-use sha2::{Digest, Sha256};
 
 #[derive(Debug)]
 pub struct MPCWallet {
     pub threshold: usize,
     pub total_participants: usize,
     pub public_key: G2Affine,
+    /// g2^{f(i)} of participant i at i - 1, to check its signature shares
+    pub public_key_shares: Vec<G2Affine>,
 }
 
 impl MPCWallet {
@@ -77,26 +79,39 @@ impl MPCWallet {
         let generator = G2Projective::generator();
         let public_key = (generator * secret_key).into_affine();
 
+        let public_key_shares = shares.iter().map(|share| share.public_key_share).collect();
+
         Ok((
             Self {
                 threshold,
                 total_participants,
                 public_key,
+                public_key_shares,
             },
             shares,
         ))
     }
 
     pub fn sign_share(message: &[u8], key_share: &KeyShare) -> SignatureShare {
-        let message_point = Self::hash_to_curve(message);
+        Self::sign_share_with_dst(message, key_share, DEFAULT_DST)
+            .expect("the default domain separation tag is valid")
+    }
+
+    /// `sign_share` with the domain separation tag `dst` instead of `DEFAULT_DST`
+    pub fn sign_share_with_dst(
+        message: &[u8],
+        key_share: &KeyShare,
+        dst: &[u8],
+    ) -> Result<SignatureShare, MPCError> {
+        let message_point = hash_to_curve(message, dst)?;
 
         // Calculate the signature share: sig_i = H(msg) * secret_share with msg, secret_share, and g are all points on the curve G1 -> sig is a point on the curve G1
         let sig_share = (message_point * key_share.secret_share).into_affine();
 
-        SignatureShare {
+        Ok(SignatureShare {
             index: key_share.index,
             sig: sig_share,
-        }
+        })
     }
 
     pub fn verify(
@@ -104,7 +119,17 @@ impl MPCWallet {
         message: &[u8],
         signature: &G1Affine,
     ) -> Result<(), MPCError> {
-        let message_point = Self::hash_to_curve(message).into_affine();
+        Self::verify_with_dst(public_key, message, signature, DEFAULT_DST)
+    }
+
+    /// `verify` with the domain separation tag `dst`, which must be the one the signature was made with
+    pub fn verify_with_dst(
+        public_key: &G2Affine,
+        message: &[u8],
+        signature: &G1Affine,
+        dst: &[u8],
+    ) -> Result<(), MPCError> {
+        let message_point = hash_to_curve(message, dst)?;
 
         let g2_generator = G2Affine::generator();
 
@@ -123,13 +148,38 @@ impl MPCWallet {
 
     pub fn combine_signature_shares(
         &self,
+        message: &[u8],
         shares: &[SignatureShare],
+    ) -> Result<G1Affine, MPCError> {
+        self.combine_signature_shares_with_dst(message, shares, DEFAULT_DST)
+    }
+
+    /// `combine_signature_shares` for shares made with `sign_share_with_dst`
+    ///
+    /// Every share is checked against its participant's public key share, e(sig_i, g2) == e(H(msg), g2^{f(i)}),
+    /// and the first `threshold` valid ones are combined. Fails with `InvalidSignatureShares` and the indices
+    /// of the invalid shares if the valid ones fall short of the threshold. On success invalid shares are
+    /// skipped silently: `verify_signature_shares` names them.
+    pub fn combine_signature_shares_with_dst(
+        &self,
+        message: &[u8],
+        shares: &[SignatureShare],
+        dst: &[u8],
     ) -> Result<G1Affine, MPCError> {
         if shares.len() < self.threshold {
             return Err(MPCError::InsufficientShares);
         }
 
-        let shares_to_use = &shares[0..self.threshold];
+        let message_point = hash_to_curve(message, dst)?;
+        let (valid, invalid) = self.check_signature_shares(&message_point, shares);
+        if valid.len() < self.threshold {
+            return Err(if invalid.is_empty() {
+                MPCError::InsufficientShares
+            } else {
+                MPCError::InvalidSignatureShares(invalid)
+            });
+        }
+        let shares_to_use = &valid[..self.threshold];
 
         let mut lagrange_coefficients = HashMap::new();
 
@@ -138,12 +188,12 @@ impl MPCWallet {
             let mut lambda_i = Fr::one();
 
             // li(x) = Π((x - xj) / (xi - xj)) for j != i
-            for j in 0..self.threshold {
+            for (j, share_j) in shares_to_use.iter().enumerate() {
                 // this stuff is equivalent to:
                 // (-1) * li(x) = (-1) * Π((-xj) / (xi - xj)) for j!= i
                 // -li(x) = Π((xj) / (xi - xj)) for j!= i
                 if i != j {
-                    let idx_j = Fr::from(shares_to_use[j].index);
+                    let idx_j = Fr::from(share_j.index);
                     let mut temp = idx_j;
                     temp -= &idx_i;
                     temp = temp.inverse().unwrap();
@@ -177,12 +227,61 @@ impl MPCWallet {
         Ok(combined_sig.into_affine())
     }
 
-    fn hash_to_curve(message: &[u8]) -> G1Projective {
-        let hash = Sha256::digest(message);
-        let mut bytes = [0u8; 32];
-        bytes.copy_from_slice(&hash[..32]);
-        let scalar = Fr::from_le_bytes_mod_order(&bytes);
-        G1Projective::generator() * scalar
+    /// The indices of the participants whose signature shares on `message` are invalid, each listed once
+    pub fn verify_signature_shares(
+        &self,
+        message: &[u8],
+        shares: &[SignatureShare],
+    ) -> Result<Vec<u32>, MPCError> {
+        self.verify_signature_shares_with_dst(message, shares, DEFAULT_DST)
+    }
+
+    /// `verify_signature_shares` for shares made with `sign_share_with_dst`
+    pub fn verify_signature_shares_with_dst(
+        &self,
+        message: &[u8],
+        shares: &[SignatureShare],
+        dst: &[u8],
+    ) -> Result<Vec<u32>, MPCError> {
+        let message_point = hash_to_curve(message, dst)?;
+        Ok(self.check_signature_shares(&message_point, shares).1)
+    }
+
+    /// Checks every share: the valid ones, one per participant, and the indices of the invalid ones
+    fn check_signature_shares<'a>(
+        &self,
+        message_point: &G1Affine,
+        shares: &'a [SignatureShare],
+    ) -> (Vec<&'a SignatureShare>, Vec<u32>) {
+        let mut valid: Vec<&SignatureShare> = Vec::with_capacity(shares.len());
+        let mut invalid = Vec::new();
+        for share in shares {
+            if !self.verify_signature_share(message_point, share) {
+                if !invalid.contains(&share.index) {
+                    invalid.push(share.index);
+                }
+            } else if !valid.iter().any(|used| used.index == share.index) {
+                valid.push(share);
+            }
+        }
+        (valid, invalid)
+    }
+
+    /// e(sig_i, g2) == e(H(msg), g2^{f(i)}), for a participant i of this wallet
+    fn verify_signature_share(&self, message_point: &G1Affine, share: &SignatureShare) -> bool {
+        let public_key_share = match (share.index as usize).checked_sub(1) {
+            Some(i) if i < self.public_key_shares.len() => self.public_key_shares[i],
+            _ => return false,
+        };
+        if !share.sig.is_in_correct_subgroup_assuming_on_curve() {
+            return false;
+        }
+
+        Bls12_381::multi_pairing(
+            [share.sig, *message_point],
+            [-G2Affine::generator(), public_key_share],
+        )
+        .is_zero()
     }
 }
 
@@ -207,10 +306,28 @@ mod tests {
     }
 
     #[test]
-    fn test_hash_to_curve() {
-        let message = b"hello world";
-        let point = MPCWallet::hash_to_curve(message);
-        println!("point: {:?}", point);
+    fn test_domain_separation_tag() {
+        let mut rng = test_rng();
+        let sk = Fr::rand(&mut rng);
+        let pk = (G2Projective::generator() * sk).into_affine();
+        let key_share = KeyShare {
+            index: 0,
+            secret_share: sk,
+            public_key_share: pk,
+        };
+
+        let message = b"hello MPC BLS!";
+        let dst = b"MY_APP_BLS12381G1_XMD:SHA-256_SSWU_RO_";
+        let sig = MPCWallet::sign_share_with_dst(message, &key_share, dst).unwrap();
+        assert!(MPCWallet::verify_with_dst(&pk, message, &sig.sig, dst).is_ok());
+        assert!(matches!(
+            MPCWallet::verify(&pk, message, &sig.sig),
+            Err(MPCError::VerificationFailed)
+        ));
+
+        // the default tag is the one of the IETF ciphersuite
+        let sig = MPCWallet::sign_share(message, &key_share);
+        assert!(MPCWallet::verify_with_dst(&pk, message, &sig.sig, DEFAULT_DST).is_ok());
     }
 
     #[test]
@@ -252,7 +369,7 @@ mod tests {
 
         // Combine the signature shares
         let signature = wallet
-            .combine_signature_shares(&[sig_share1, sig_share2, sig_share3])
+            .combine_signature_shares(message, &[sig_share1, sig_share2, sig_share3])
             .map_err(|e| {
                 println!("Error combining signature shares: {:?}", e);
                 e
@@ -266,4 +383,69 @@ mod tests {
             .map_err(|e| println!("Error verifying signature: {:?}", e));
         assert!(result.is_ok(), "Signature should verify");
     }
+
+    #[test]
+    fn test_invalid_signature_shares_are_skipped() {
+        let mut rng = test_rng();
+        let (wallet, shares) = MPCWallet::keygen(3, 5, &mut rng).unwrap();
+        let message = b"Send 1 BTC to Alice";
+        let mut signature_shares: Vec<SignatureShare> = shares
+            .iter()
+            .map(|share| MPCWallet::sign_share(message, share))
+            .collect();
+
+        // participant 2 signs another message, participant 3 sends a random point
+        signature_shares[1] = MPCWallet::sign_share(b"Send 100 BTC to Mallory", &shares[1]);
+        signature_shares[2].sig = G1Projective::rand(&mut rng).into_affine();
+
+        let signature = wallet
+            .combine_signature_shares(message, &signature_shares)
+            .unwrap();
+        assert!(MPCWallet::verify(&wallet.public_key, message, &signature).is_ok());
+
+        // the culprits are found even when the signature goes through, every share is checked
+        let mut resent = signature_shares.clone();
+        resent.push(signature_shares[1].clone());
+        assert_eq!(
+            wallet.verify_signature_shares(message, &resent).unwrap(),
+            vec![2, 3]
+        );
+        let twice = [
+            signature_shares[1].clone(),
+            signature_shares[1].clone(),
+            signature_shares[0].clone(),
+            signature_shares[3].clone(),
+        ];
+        assert!(matches!(
+            wallet.combine_signature_shares(message, &twice),
+            Err(MPCError::InvalidSignatureShares(invalid)) if invalid == vec![2]
+        ));
+
+        // with participant 5 missing, only 2 valid shares are left
+        assert!(matches!(
+            wallet.combine_signature_shares(message, &signature_shares[..4]),
+            Err(MPCError::InvalidSignatureShares(invalid)) if invalid == vec![2, 3]
+        ));
+
+        // duplicates and unknown participants do not count towards the threshold
+        let mut unknown = signature_shares[0].clone();
+        unknown.index = 6;
+        let shares = [
+            signature_shares[0].clone(),
+            signature_shares[0].clone(),
+            unknown,
+            signature_shares[3].clone(),
+        ];
+        assert!(matches!(
+            wallet.combine_signature_shares(message, &shares),
+            Err(MPCError::InvalidSignatureShares(invalid)) if invalid == vec![6]
+        ));
+        assert!(matches!(
+            wallet.combine_signature_shares(
+                message,
+                &[shares[0].clone(), shares[0].clone(), shares[3].clone()]
+            ),
+            Err(MPCError::InsufficientShares)
+        ));
+    }
 }
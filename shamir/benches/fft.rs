@@ -0,0 +1,68 @@
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use shamir::fft::{generate_shares_fft_with_rng, reconstruct_secret_fft};
+use shamir::{
+    Fp255, Goldilocks, Polynomial, PrimeField, generate_shares_with_rng, reconstruct_secret,
+};
+
+const COMMITTEE_SIZES: [u32; 3] = [256, 1024, 4096];
+
+fn share_generation(c: &mut Criterion) {
+    let mut group = c.benchmark_group("generate_shares");
+    group.sample_size(10);
+    for n in COMMITTEE_SIZES {
+        let secret = Goldilocks::from_u64(42);
+        let threshold = n / 2;
+        group.bench_with_input(BenchmarkId::new("naive", n), &n, |b, &n| {
+            b.iter(|| generate_shares_with_rng(secret, threshold, n, &mut StdRng::seed_from_u64(0)))
+        });
+        group.bench_with_input(BenchmarkId::new("fft", n), &n, |b, &n| {
+            b.iter(|| {
+                generate_shares_fft_with_rng(secret, threshold, n, &mut StdRng::seed_from_u64(0))
+            })
+        });
+    }
+    group.finish();
+}
+
+fn reconstruction(c: &mut Criterion) {
+    let mut group = c.benchmark_group("reconstruct_secret");
+    group.sample_size(10);
+    // naive reconstruction inverts n^2 values: 4096 parties take tens of seconds per run
+    for n in [256, 1024, 2048] {
+        let shares = generate_shares_with_rng(
+            Goldilocks::from_u64(42),
+            n,
+            n,
+            &mut StdRng::seed_from_u64(0),
+        )
+        .unwrap();
+        group.bench_with_input(BenchmarkId::new("naive", n), &shares, |b, shares| {
+            b.iter(|| reconstruct_secret(shares))
+        });
+        group.bench_with_input(BenchmarkId::new("fft", n), &shares, |b, shares| {
+            b.iter(|| reconstruct_secret_fft(shares))
+        });
+    }
+    group.finish();
+}
+
+fn multiplication(c: &mut Criterion) {
+    let mut group = c.benchmark_group("polynomial_mul_fr");
+    let mut rng = StdRng::seed_from_u64(0);
+    for len in [64, 512, 4096] {
+        let a = Polynomial::new((0..len).map(|_| Fp255::random(&mut rng)).collect());
+        let b = Polynomial::new((0..len).map(|_| Fp255::random(&mut rng)).collect());
+        group.bench_with_input(BenchmarkId::new("schoolbook", len), &len, |bench, _| {
+            bench.iter(|| &a * &b)
+        });
+        group.bench_with_input(BenchmarkId::new("fft", len), &len, |bench, _| {
+            bench.iter(|| a.mul_fft(&b))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, share_generation, reconstruction, multiplication);
+criterion_main!(benches);
@@ -0,0 +1,468 @@
+use rand::CryptoRng;
+
+use crate::errors::ShamirError;
+use crate::ff_ops::PrimeField;
+use crate::polynomial::{Polynomial, generate_polynomial_with_rng};
+use crate::robust::solve_linear_system;
+use crate::share::{Share, check_field_ids, check_parameters, validate_shares};
+use crate::{generate_shares_with_rng, interpolate_at_zero};
+
+/// Who may reconstruct the secret. Parties are numbered from 0, in the order they are listed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AccessStructure {
+    /// Party i holds `weights[i]` ordinary Shamir shares, any parties holding `threshold` shares
+    /// together reconstruct
+    Weighted { weights: Vec<u32>, threshold: u32 },
+    /// Tassa's conjunctive hierarchy, most senior level first: a set of parties is authorized when,
+    /// for every level, it has at least `threshold` members from that level or a more senior one
+    Hierarchical { levels: Vec<Level> },
+}
+
+/// One level of a hierarchical access structure
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Level {
+    pub parties: u32,
+    /// Cumulative: counts the members of this level and of all more senior levels
+    pub threshold: u32,
+}
+
+impl AccessStructure {
+    /// Number of parties
+    pub fn parties(&self) -> usize {
+        match self {
+            AccessStructure::Weighted { weights, .. } => weights.len(),
+            AccessStructure::Hierarchical { levels } => {
+                levels.iter().map(|level| level.parties as usize).sum()
+            }
+        }
+    }
+
+    /// Whether the parties, given by index, may reconstruct the secret together.
+    /// Repeated and unknown indices count once and not at all, respectively.
+    pub fn can_reconstruct(&self, parties: &[usize]) -> bool {
+        let mut members: Vec<usize> = parties
+            .iter()
+            .copied()
+            .filter(|&party| party < self.parties())
+            .collect();
+        members.sort_unstable();
+        members.dedup();
+
+        match self {
+            AccessStructure::Weighted { weights, threshold } => {
+                let weight: u64 = members.iter().map(|&party| weights[party] as u64).sum();
+                weight >= *threshold as u64
+            }
+            AccessStructure::Hierarchical { levels } => {
+                let mut senior = 0;
+                levels.iter().enumerate().all(|(i, level)| {
+                    senior += members
+                        .iter()
+                        .filter(|&&party| self.level_of(party) == Some(i))
+                        .count();
+                    senior >= level.threshold as usize
+                })
+            }
+        }
+    }
+
+    /// The party holding share `id`
+    pub fn party_of(&self, id: u32) -> Option<usize> {
+        match self {
+            AccessStructure::Weighted { weights, .. } => {
+                let mut first = 1u64;
+                weights.iter().position(|&weight| {
+                    let holds = (first..first + weight as u64).contains(&(id as u64));
+                    first += weight as u64;
+                    holds
+                })
+            }
+            AccessStructure::Hierarchical { .. } => {
+                let party = (id as usize).checked_sub(1)?;
+                (party < self.parties()).then_some(party)
+            }
+        }
+    }
+
+    /// Splits the secret into shares, one `Vec` per party
+    pub fn generate_shares<F: PrimeField>(
+        &self,
+        secret: F,
+    ) -> Result<Vec<Vec<Share<F>>>, ShamirError> {
+        self.generate_shares_with_rng(secret, &mut rand::rng())
+    }
+
+    /// ```md
+    /// Weighted: one Shamir sharing with Σ(weights) shares, party i gets the next weights[i] of them.
+    ///
+    /// Hierarchical: f of degree t_m - 1 with f(0) = secret, t_m the threshold of the last level.
+    /// Party j gets id j + 1, parties of more senior levels get smaller ids, and level i hands out
+    /// derivatives:
+    ///
+    /// share_j = f^(k_i)(j + 1), k_i = t_{i-1} and k_0 = 0
+    ///
+    /// A junior share carries no information about the k_i lowest coefficients of f, so juniors
+    /// need enough seniors with them to pin those down.
+    /// ```
+    pub fn generate_shares_with_rng<F: PrimeField>(
+        &self,
+        secret: F,
+        rng: &mut impl CryptoRng,
+    ) -> Result<Vec<Vec<Share<F>>>, ShamirError> {
+        self.check()?;
+
+        match self {
+            AccessStructure::Weighted { weights, threshold } => {
+                let total = weights.iter().sum();
+                let mut shares = generate_shares_with_rng(secret, *threshold, total, rng)?;
+                Ok(weights
+                    .iter()
+                    .map(|&weight| shares.drain(..weight as usize).collect())
+                    .collect())
+            }
+            AccessStructure::Hierarchical { levels } => {
+                let ids: Vec<u32> = (1..=self.parties() as u32).collect();
+                check_field_ids::<F>(&ids)?;
+
+                let threshold = self.threshold();
+                let mut derivative =
+                    Polynomial::new(generate_polynomial_with_rng(secret, threshold, rng)?);
+                let set_id = rng.next_u64();
+
+                let mut shares = Vec::with_capacity(ids.len());
+                let mut order = 0;
+                let mut ids = ids.into_iter();
+                for (i, level) in levels.iter().enumerate() {
+                    for _ in order..level_order(levels, i) {
+                        derivative = derivative.derivative();
+                    }
+                    order = level_order(levels, i);
+
+                    for id in ids.by_ref().take(level.parties as usize) {
+                        shares.push(vec![Share {
+                            id,
+                            value: derivative.evaluate(F::from_u64(id as u64)),
+                            threshold,
+                            set_id,
+                        }]);
+                    }
+                }
+                Ok(shares)
+            }
+        }
+    }
+
+    /// ```md
+    /// Weighted: plain Lagrange interpolation, see `reconstruct_secret`.
+    ///
+    /// Hierarchical: Birkhoff interpolation. Each share is a linear equation in the coefficients
+    /// a_0..a_{t_m - 1} of f:
+    ///
+    /// f^(k)(x) = Σ(a_j * j! / (j - k)! * x^(j - k)) for j >= k
+    ///
+    /// and an authorized set gives a system of full rank, whose solution has a_0 = secret.
+    /// Tassa shows it does whenever the field is large enough for the number of parties;
+    /// in a small field `DegenerateShares` is possible. Shares of senior parties alone are
+    /// ordinary Shamir shares and go through Lagrange interpolation.
+    /// ```
+    pub fn reconstruct_secret<F: PrimeField>(&self, shares: &[Share<F>]) -> Result<F, ShamirError> {
+        self.check()?;
+        validate_shares(shares)?;
+        let ids: Vec<u32> = shares.iter().map(|share| share.id).collect();
+        check_field_ids::<F>(&ids)?;
+
+        let parties = ids
+            .iter()
+            .map(|&id| self.party_of(id).ok_or(ShamirError::InvalidShareId(id)))
+            .collect::<Result<Vec<usize>, _>>()?;
+
+        let AccessStructure::Hierarchical { levels } = self else {
+            return crate::reconstruct_secret(shares);
+        };
+        if !self.can_reconstruct(&parties) {
+            return Err(ShamirError::UnauthorizedShares);
+        }
+
+        let orders: Vec<u32> = parties
+            .iter()
+            .map(|&party| {
+                level_order(
+                    levels,
+                    self.level_of(party).expect("a party of the hierarchy"),
+                )
+            })
+            .collect();
+        if orders.iter().all(|&order| order == 0) {
+            return Ok(interpolate_at_zero(
+                &ids,
+                shares.iter().map(|share| share.value),
+            ));
+        }
+
+        let unknowns = self.threshold() as usize;
+        let matrix = ids
+            .iter()
+            .zip(&orders)
+            .map(|(&id, &order)| birkhoff_row(F::from_u64(id as u64), order, unknowns))
+            .collect();
+        let rhs = shares.iter().map(|share| share.value).collect();
+
+        match solve_linear_system(matrix, rhs) {
+            Some((coefficients, rank)) if rank == unknowns => Ok(coefficients[0]),
+            Some(_) => Err(ShamirError::DegenerateShares),
+            // authorized shares of one sharing always agree
+            None => Err(ShamirError::MismatchedShares),
+        }
+    }
+
+    /// Number of shares below which nobody reconstructs, the degree of the polynomial + 1
+    fn threshold(&self) -> u32 {
+        match self {
+            AccessStructure::Weighted { threshold, .. } => *threshold,
+            AccessStructure::Hierarchical { levels } => levels.last().map_or(0, |l| l.threshold),
+        }
+    }
+
+    fn level_of(&self, party: usize) -> Option<usize> {
+        let AccessStructure::Hierarchical { levels } = self else {
+            return None;
+        };
+        let mut first = 0;
+        levels.iter().position(|level| {
+            first += level.parties as usize;
+            party < first
+        })
+    }
+
+    /// Rejects thresholds no set of parties can reach, and hierarchies whose thresholds
+    /// do not grow from level to level
+    fn check(&self) -> Result<(), ShamirError> {
+        match self {
+            AccessStructure::Weighted { weights, threshold } => {
+                let total = weights
+                    .iter()
+                    .try_fold(0u32, |total, &weight| total.checked_add(weight));
+                check_parameters(*threshold, total.unwrap_or(u32::MAX))
+            }
+            AccessStructure::Hierarchical { levels } => {
+                let mut parties = 0u32;
+                let mut previous = 0;
+                for level in levels {
+                    parties = parties.saturating_add(level.parties);
+                    check_parameters(level.threshold, parties)?;
+                    if level.threshold <= previous {
+                        return Err(ShamirError::InvalidThreshold {
+                            threshold: level.threshold,
+                            total_shares: parties,
+                        });
+                    }
+                    previous = level.threshold;
+                }
+                check_parameters(previous, parties)
+            }
+        }
+    }
+}
+
+/// k_i, the derivative handed out by level i: the threshold of the level above it
+fn level_order(levels: &[Level], i: usize) -> u32 {
+    i.checked_sub(1).map_or(0, |above| levels[above].threshold)
+}
+
+/// The coefficients of f^(k)(x) in a_0..a_{unknowns - 1}
+fn birkhoff_row<F: PrimeField>(x: F, order: u32, unknowns: usize) -> Vec<F> {
+    let order = order as usize;
+    let mut row = vec![F::zero(); unknowns];
+    let mut power = F::one();
+    for (j, entry) in row.iter_mut().enumerate().skip(order) {
+        // j! / (j - k)! = j * (j - 1) * ... * (j - k + 1)
+        let falling = (j - order + 1..=j).fold(F::one(), |acc, m| acc * F::from_u64(m as u64));
+        *entry = falling * power;
+        power = power * x;
+    }
+    row
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Fp, Fp255};
+
+    const PRIME: u64 = 997;
+
+    /// every subset of the parties, as a list of indices
+    fn subsets(parties: usize) -> impl Iterator<Item = Vec<usize>> {
+        (0u32..1 << parties).map(move |mask| (0..parties).filter(|&i| mask >> i & 1 == 1).collect())
+    }
+
+    fn shares_of<F: Clone>(shares: &[Vec<Share<F>>], parties: &[usize]) -> Vec<Share<F>> {
+        parties.iter().flat_map(|&i| shares[i].clone()).collect()
+    }
+
+    #[test]
+    fn test_custody_policy_as_weights() {
+        // "2 executives or 1 executive + 3 engineers": executives weigh 3, engineers 1,
+        // which holds as long as there are fewer than 6 engineers
+        let structure = AccessStructure::Weighted {
+            weights: vec![3, 3, 3, 1, 1, 1, 1, 1],
+            threshold: 6,
+        };
+        let policy = |set: &[usize]| {
+            let executives = set.iter().filter(|&&i| i < 3).count();
+            executives >= 2 || (executives == 1 && set.len() >= 4)
+        };
+
+        let secret = Fp255::from_u64(0xc0ffee);
+        let shares = structure.generate_shares(secret).unwrap();
+        assert_eq!(shares.iter().map(Vec::len).sum::<usize>(), 14);
+
+        for set in subsets(8) {
+            assert_eq!(structure.can_reconstruct(&set), policy(&set), "{set:?}");
+            let result = structure.reconstruct_secret(&shares_of(&shares, &set));
+            if policy(&set) {
+                assert_eq!(result, Ok(secret));
+            } else {
+                assert!(result.is_err());
+            }
+        }
+    }
+
+    #[test]
+    fn test_weighted_party_of() {
+        let structure = AccessStructure::Weighted {
+            weights: vec![2, 0, 3],
+            threshold: 3,
+        };
+        let ids: Vec<Option<usize>> = (0..7).map(|id| structure.party_of(id)).collect();
+        assert_eq!(
+            ids,
+            [None, Some(0), Some(0), Some(2), Some(2), Some(2), None]
+        );
+        assert!(structure.can_reconstruct(&[2, 2, 9]));
+        assert!(!structure.can_reconstruct(&[0, 1]));
+    }
+
+    #[test]
+    fn test_hierarchical_sharing() {
+        // at least 1 executive, and 4 people overall
+        let structure = AccessStructure::Hierarchical {
+            levels: vec![
+                Level {
+                    parties: 2,
+                    threshold: 1,
+                },
+                Level {
+                    parties: 5,
+                    threshold: 4,
+                },
+            ],
+        };
+        let policy = |set: &[usize]| set.iter().any(|&i| i < 2) && set.len() >= 4;
+
+        let secret = Fp255::from_u64(31337);
+        let shares = structure.generate_shares(secret).unwrap();
+        for set in subsets(7) {
+            assert_eq!(structure.can_reconstruct(&set), policy(&set), "{set:?}");
+            let result = structure.reconstruct_secret(&shares_of(&shares, &set));
+            match (policy(&set), set.len()) {
+                (true, _) => assert_eq!(result, Ok(secret), "{set:?}"),
+                (false, 0..4) => assert!(matches!(
+                    result,
+                    Err(ShamirError::InsufficientShares { .. })
+                )),
+                (false, _) => assert_eq!(result, Err(ShamirError::UnauthorizedShares)),
+            }
+        }
+    }
+
+    #[test]
+    fn test_juniors_alone_do_not_determine_the_secret() {
+        // three levels: 1 of the 2 directors, 3 with the managers, 5 with the staff
+        let levels = vec![
+            Level {
+                parties: 2,
+                threshold: 1,
+            },
+            Level {
+                parties: 3,
+                threshold: 3,
+            },
+            Level {
+                parties: 4,
+                threshold: 5,
+            },
+        ];
+        let structure = AccessStructure::Hierarchical {
+            levels: levels.clone(),
+        };
+        let shares = structure
+            .generate_shares(Fp::<PRIME>::from_u64(42))
+            .unwrap();
+
+        // managers and staff hold derivatives, so 7 of them still miss the constant term
+        let orders = [1, 1, 1, 3, 3, 3, 3];
+        let matrix: Vec<Vec<Fp<PRIME>>> = (3..=9)
+            .zip(orders)
+            .map(|(id, order)| birkhoff_row(Fp::from_u64(id), order, 5))
+            .collect();
+        let rhs = shares_of(&shares, &[2, 3, 4, 5, 6, 7, 8])
+            .iter()
+            .map(|share| share.value)
+            .collect();
+        let (_, rank) = solve_linear_system(matrix, rhs).unwrap();
+        assert_eq!(rank, 4);
+
+        // one director is enough to complete them
+        let set = [0, 3, 4, 5, 8];
+        assert!(structure.can_reconstruct(&set));
+        assert_eq!(
+            structure.reconstruct_secret(&shares_of(&shares, &set)),
+            Ok(Fp::from_u64(42))
+        );
+    }
+
+    #[test]
+    fn test_invalid_structures_are_rejected() {
+        let secret = Fp255::from_u64(1);
+        let weighted = AccessStructure::Weighted {
+            weights: vec![1, 2],
+            threshold: 4,
+        };
+        assert_eq!(
+            weighted.generate_shares(secret),
+            Err(ShamirError::InvalidThreshold {
+                threshold: 4,
+                total_shares: 3
+            })
+        );
+
+        let flat = AccessStructure::Hierarchical {
+            levels: vec![
+                Level {
+                    parties: 3,
+                    threshold: 2,
+                },
+                Level {
+                    parties: 3,
+                    threshold: 2,
+                },
+            ],
+        };
+        assert_eq!(
+            flat.generate_shares(secret),
+            Err(ShamirError::InvalidThreshold {
+                threshold: 2,
+                total_shares: 6
+            })
+        );
+
+        let empty = AccessStructure::Hierarchical { levels: vec![] };
+        assert_eq!(
+            empty.generate_shares(secret),
+            Err(ShamirError::InvalidThreshold {
+                threshold: 0,
+                total_shares: 0
+            })
+        );
+    }
+}
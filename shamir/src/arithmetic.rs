@@ -0,0 +1,242 @@
+use sha2::{Digest, Sha256};
+
+use crate::errors::ShamirError;
+use crate::ff_ops::PrimeField;
+use crate::reshare::redistribute;
+use crate::share::{Share, check_parameters};
+
+/// ```md
+/// Share arithmetic: Shamir sharing is linear, so parties compute on their own shares
+///
+/// f(i) + g(i) = (f + g)(i)    (f + g)(0) = a + b
+/// c * f(i)    = (c * f)(i)    (c * f)(0) = c * a
+/// f(i) + c    = (f + c)(i)    (f + c)(0) = a + c
+///
+/// The result belongs to a new share set. Its set id is derived from the inputs, so every party
+/// computes the same one without talking to the others.
+/// ```
+///
+/// Party i's share of a + b
+pub fn add_shares<F: PrimeField>(a: &Share<F>, b: &Share<F>) -> Result<Share<F>, ShamirError> {
+    if a.id != b.id {
+        return Err(ShamirError::MismatchedShares);
+    }
+    Ok(Share {
+        id: a.id,
+        value: a.value + b.value,
+        threshold: a.threshold.max(b.threshold),
+        set_id: derive_set_id(b"add", &[a.set_id, b.set_id], &[]),
+    })
+}
+
+/// Party i's share of c * a
+pub fn multiply_by_constant<F: PrimeField>(share: &Share<F>, constant: F) -> Share<F> {
+    Share {
+        value: share.value * constant,
+        set_id: derive_set_id(b"mul_constant", &[share.set_id], &constant.to_bytes()),
+        ..share.clone()
+    }
+}
+
+/// Party i's share of a + c: every party adds c, since the polynomial's constant term moves by c
+pub fn add_constant<F: PrimeField>(share: &Share<F>, constant: F) -> Share<F> {
+    Share {
+        value: share.value + constant,
+        set_id: derive_set_id(b"add_constant", &[share.set_id], &constant.to_bytes()),
+        ..share.clone()
+    }
+}
+
+/// ```md
+/// Party i's share of a * b, on a polynomial of twice the degree:
+///
+/// f(i) * g(i) = (f * g)(i),   deg(f * g) = 2(t - 1)
+///
+/// so 2t - 1 of these shares are needed to reconstruct, and a second multiplication would
+/// need even more: `bgw_multiply` brings the degree back down.
+/// ```
+pub fn multiply_shares_local<F: PrimeField>(
+    a: &Share<F>,
+    b: &Share<F>,
+) -> Result<Share<F>, ShamirError> {
+    if a.id != b.id {
+        return Err(ShamirError::MismatchedShares);
+    }
+    Ok(Share {
+        id: a.id,
+        value: a.value * b.value,
+        threshold: product_threshold(a.threshold, b.threshold)?,
+        set_id: derive_set_id(b"mul", &[a.set_id, b.set_id], &[]),
+    })
+}
+
+/// ```md
+/// BGW multiplication, simulated for all parties
+///
+/// 1. every party multiplies its shares locally: h(i) = f(i) * g(i), deg h = 2t - 2
+/// 2. degree reduction: every party reshares h(i) with a fresh polynomial of degree t - 1,
+///    and combines what it receives with the Lagrange weights of the 2t - 1 or more senders
+///    (`reshare::redistribute`)
+///
+/// The result is a fresh t-of-n sharing of a * b. It needs n >= 2t - 1, i.e. t - 1 < n / 2:
+/// an honest majority.
+/// ```
+///
+/// `a` and `b` hold one share per party, for the same parties.
+pub fn bgw_multiply<F: PrimeField>(
+    a: &[Share<F>],
+    b: &[Share<F>],
+) -> Result<Vec<Share<F>>, ShamirError> {
+    if a.len() != b.len() {
+        return Err(ShamirError::MismatchedShares);
+    }
+    let threshold = a
+        .iter()
+        .chain(b)
+        .map(|share| share.threshold)
+        .max()
+        .unwrap_or(1);
+    check_parameters(product_threshold(threshold, threshold)?, a.len() as u32)?;
+
+    let products = a
+        .iter()
+        .zip(b)
+        .map(|(a, b)| multiply_shares_local(a, b))
+        .collect::<Result<Vec<_>, _>>()?;
+    let ids: Vec<u32> = products.iter().map(|share| share.id).collect();
+    redistribute(&products, threshold, &ids)
+}
+
+/// s + t - 1, the number of shares of a product of s-of-n and t-of-n shares needed to
+/// reconstruct it. Fails for a threshold of 0, which no sharing has.
+pub(crate) fn product_threshold(s: u32, t: u32) -> Result<u32, ShamirError> {
+    if s == 0 || t == 0 {
+        return Err(ShamirError::InvalidThreshold {
+            threshold: 0,
+            total_shares: 0,
+        });
+    }
+    (s - 1).checked_add(t).ok_or(ShamirError::InvalidThreshold {
+        threshold: s.max(t),
+        total_shares: 0,
+    })
+}
+
+fn derive_set_id(operation: &[u8], set_ids: &[u64], constant: &[u8]) -> u64 {
+    let mut hasher = Sha256::new();
+    hasher.update(operation);
+    for set_id in set_ids {
+        hasher.update(set_id.to_be_bytes());
+    }
+    hasher.update(constant);
+    let digest = hasher.finalize();
+    u64::from_be_bytes(digest[..8].try_into().expect("SHA-256 has 32 bytes"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Fp, Fp255, generate_shares, reconstruct_secret};
+
+    const PRIME: u64 = 997;
+
+    type F = Fp<PRIME>;
+
+    fn apply<F: PrimeField>(
+        a: &[Share<F>],
+        b: &[Share<F>],
+        op: impl Fn(&Share<F>, &Share<F>) -> Result<Share<F>, ShamirError>,
+    ) -> Vec<Share<F>> {
+        a.iter().zip(b).map(|(a, b)| op(a, b).unwrap()).collect()
+    }
+
+    #[test]
+    fn test_linear_operations() {
+        let a = generate_shares(F::from_u64(400), 3, 5).unwrap();
+        let b = generate_shares(F::from_u64(700), 3, 5).unwrap();
+
+        let sum = apply(&a, &b, add_shares);
+        assert_eq!(reconstruct_secret(&sum[2..]), Ok(F::from_u64(103)));
+
+        let scaled: Vec<_> = a
+            .iter()
+            .map(|s| multiply_by_constant(s, F::from_u64(3)))
+            .collect();
+        assert_eq!(reconstruct_secret(&scaled[..3]), Ok(F::from_u64(203)));
+
+        let shifted: Vec<_> = b
+            .iter()
+            .map(|s| add_constant(s, F::from_u64(300)))
+            .collect();
+        assert_eq!(reconstruct_secret(&shifted[1..4]), Ok(F::from_u64(3)));
+
+        // results form their own share set, the same at every party
+        assert!(sum.iter().all(|share| share.set_id == sum[0].set_id));
+        assert_ne!(sum[0].set_id, a[0].set_id);
+        assert_eq!(
+            reconstruct_secret(&[sum[0].clone(), a[1].clone(), a[2].clone()]),
+            Err(ShamirError::MismatchedShares)
+        );
+        assert_eq!(add_shares(&a[0], &b[1]), Err(ShamirError::MismatchedShares));
+    }
+
+    #[test]
+    fn test_local_product_doubles_the_degree() {
+        let a = generate_shares(F::from_u64(12), 3, 5).unwrap();
+        let b = generate_shares(F::from_u64(34), 3, 5).unwrap();
+
+        let product = apply(&a, &b, multiply_shares_local);
+        assert!(product.iter().all(|share| share.threshold == 5));
+        assert_eq!(reconstruct_secret(&product), Ok(F::from_u64(12 * 34)));
+        assert!(reconstruct_secret(&product[..3]).is_err());
+    }
+
+    #[test]
+    fn test_bgw_multiply() {
+        let a = generate_shares(Fp255::from_u64(1 << 40), 3, 5).unwrap();
+        let b = generate_shares(Fp255::from_u64(1 << 41), 3, 5).unwrap();
+
+        let product = bgw_multiply(&a, &b).unwrap();
+        assert!(product.iter().all(|share| share.threshold == 3));
+        let expected = Fp255::from_u64(1 << 40) * Fp255::from_u64(1 << 41);
+        assert_eq!(reconstruct_secret(&product[2..]), Ok(expected));
+
+        // degree t - 1 again, so products multiply further
+        let cube = bgw_multiply(&product, &a).unwrap();
+        assert_eq!(
+            reconstruct_secret(&cube[..3]),
+            Ok(expected * Fp255::from_u64(1 << 40))
+        );
+    }
+
+    #[test]
+    fn test_zero_threshold_is_rejected() {
+        let a = generate_shares(F::from_u64(2), 2, 3).unwrap();
+        let mut b = generate_shares(F::from_u64(5), 2, 3).unwrap();
+        for share in &mut b {
+            share.threshold = 0;
+        }
+
+        let error = || ShamirError::InvalidThreshold {
+            threshold: 0,
+            total_shares: 0,
+        };
+        assert_eq!(multiply_shares_local(&a[0], &b[0]), Err(error()));
+        assert_eq!(multiply_shares_local(&b[0], &b[0]), Err(error()));
+        assert_eq!(bgw_multiply(&b, &b), Err(error()));
+        assert_eq!(bgw_multiply(&a, &b), Err(error()));
+    }
+
+    #[test]
+    fn test_bgw_needs_an_honest_majority() {
+        let a = generate_shares(F::from_u64(2), 3, 4).unwrap();
+        let b = generate_shares(F::from_u64(5), 3, 4).unwrap();
+        assert_eq!(
+            bgw_multiply(&a, &b),
+            Err(ShamirError::InvalidThreshold {
+                threshold: 5,
+                total_shares: 4
+            })
+        );
+    }
+}
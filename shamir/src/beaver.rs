@@ -0,0 +1,167 @@
+use rand::CryptoRng;
+
+use crate::arithmetic::{add_constant, add_shares, multiply_by_constant};
+use crate::errors::ShamirError;
+use crate::ff_ops::PrimeField;
+use crate::share::{Share, check_parameters};
+use crate::{generate_shares_with_rng, reconstruct_secret};
+
+/// One party's shares of a Beaver triple: random a and b, and c = a * b
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BeaverTriple<F> {
+    pub a: Share<F>,
+    pub b: Share<F>,
+    pub c: Share<F>,
+}
+
+/// Trusted dealer: `count` triples Shamir-shared among `parties` with `threshold`,
+/// `triples[k][i]` is party i's share of the k-th triple
+pub fn deal_triples<F: PrimeField>(
+    count: usize,
+    threshold: u32,
+    parties: u32,
+) -> Result<Vec<Vec<BeaverTriple<F>>>, ShamirError> {
+    deal_triples_with_rng(count, threshold, parties, &mut rand::rng())
+}
+
+/// `deal_triples` with randomness from `rng`
+pub fn deal_triples_with_rng<F: PrimeField>(
+    count: usize,
+    threshold: u32,
+    parties: u32,
+    rng: &mut impl CryptoRng,
+) -> Result<Vec<Vec<BeaverTriple<F>>>, ShamirError> {
+    check_parameters(threshold, parties)?;
+
+    (0..count)
+        .map(|_| {
+            let a = F::random(rng);
+            let b = F::random(rng);
+            let a_shares = generate_shares_with_rng(a, threshold, parties, rng)?;
+            let b_shares = generate_shares_with_rng(b, threshold, parties, rng)?;
+            let c_shares = generate_shares_with_rng(a * b, threshold, parties, rng)?;
+
+            Ok(a_shares
+                .into_iter()
+                .zip(b_shares)
+                .zip(c_shares)
+                .map(|((a, b), c)| BeaverTriple { a, b, c })
+                .collect())
+        })
+        .collect()
+}
+
+/// ```md
+/// Beaver multiplication, simulated for all parties
+///
+/// With a triple (a, b, c = a * b), the parties open
+///
+/// d = x - a,  e = y - b
+///
+/// which are uniformly random and reveal nothing about x and y, and then compute locally
+///
+/// z_i = c_i + d * b_i + e * a_i + d * e
+///     = shares of ab + (x - a)b + (y - b)a + (x - a)(y - b) = xy
+///
+/// Unlike `bgw_multiply`, the result keeps the degree of the triple, so it works with any threshold,
+/// and the only online work is opening two values. Every triple is used once.
+/// ```
+///
+/// `x`, `y` and `triple` hold one share per party, for the same parties.
+pub fn beaver_multiply<F: PrimeField>(
+    x: &[Share<F>],
+    y: &[Share<F>],
+    triple: &[BeaverTriple<F>],
+) -> Result<Vec<Share<F>>, ShamirError> {
+    if x.len() != y.len() || x.len() != triple.len() {
+        return Err(ShamirError::MismatchedShares);
+    }
+    let minus_one = -F::one();
+
+    let d_shares = x
+        .iter()
+        .zip(triple)
+        .map(|(x, t)| add_shares(x, &multiply_by_constant(&t.a, minus_one)))
+        .collect::<Result<Vec<_>, _>>()?;
+    let e_shares = y
+        .iter()
+        .zip(triple)
+        .map(|(y, t)| add_shares(y, &multiply_by_constant(&t.b, minus_one)))
+        .collect::<Result<Vec<_>, _>>()?;
+    let d = reconstruct_secret(&d_shares)?;
+    let e = reconstruct_secret(&e_shares)?;
+
+    triple
+        .iter()
+        .map(|t| {
+            let z = add_shares(&t.c, &multiply_by_constant(&t.b, d))?;
+            let z = add_shares(&z, &multiply_by_constant(&t.a, e))?;
+            Ok(add_constant(&z, d * e))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Fp, Fp255, generate_shares};
+
+    const PRIME: u64 = 997;
+
+    type F = Fp<PRIME>;
+
+    #[test]
+    fn test_triples_are_consistent() {
+        let triples = deal_triples::<F>(3, 2, 4).unwrap();
+        assert_eq!(triples.len(), 3);
+
+        for triple in &triples {
+            assert_eq!(triple.len(), 4);
+            let open = |pick: fn(&BeaverTriple<F>) -> &Share<F>| {
+                let shares: Vec<Share<F>> = triple.iter().map(|t| pick(t).clone()).collect();
+                reconstruct_secret(&shares).unwrap()
+            };
+            assert_eq!(open(|t| &t.a) * open(|t| &t.b), open(|t| &t.c));
+        }
+    }
+
+    #[test]
+    fn test_beaver_multiply() {
+        // 3-of-4: too high a threshold for BGW, fine with triples
+        let x = generate_shares(F::from_u64(123), 3, 4).unwrap();
+        let y = generate_shares(F::from_u64(456), 3, 4).unwrap();
+        let triples = deal_triples::<F>(1, 3, 4).unwrap();
+
+        let z = beaver_multiply(&x, &y, &triples[0]).unwrap();
+        assert!(z.iter().all(|share| share.threshold == 3));
+        assert_eq!(reconstruct_secret(&z[1..]), Ok(F::from_u64(123 * 456)));
+    }
+
+    #[test]
+    fn test_dot_product() {
+        let xs: Vec<Fp255> = (1..=8).map(|i| Fp255::from_u64(i * 1000)).collect();
+        let ys: Vec<Fp255> = (1..=8).map(|i| Fp255::from_u64(i + 7)).collect();
+        let expected = xs
+            .iter()
+            .zip(&ys)
+            .fold(Fp255::zero(), |acc, (&x, &y)| acc + x * y);
+
+        let triples = deal_triples::<Fp255>(xs.len(), 2, 3).unwrap();
+        let mut sum: Option<Vec<Share<Fp255>>> = None;
+        for ((&x, &y), triple) in xs.iter().zip(&ys).zip(&triples) {
+            let x = generate_shares(x, 2, 3).unwrap();
+            let y = generate_shares(y, 2, 3).unwrap();
+            let product = beaver_multiply(&x, &y, triple).unwrap();
+            sum = Some(match sum {
+                None => product,
+                Some(sum) => sum
+                    .iter()
+                    .zip(&product)
+                    .map(|(a, b)| add_shares(a, b).unwrap())
+                    .collect(),
+            });
+        }
+
+        assert_eq!(reconstruct_secret(&sum.unwrap()[1..]), Ok(expected));
+    }
+}
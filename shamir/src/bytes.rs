@@ -0,0 +1,361 @@
+use rand::RngCore;
+
+use crate::errors::ShamirError;
+use crate::ff_ops::{Fp255, PrimeField};
+use crate::gf256::{gf_add, gf_mul_ct, interpolate};
+use crate::interpolate_at_zero;
+use crate::polynomial::{evaluate_polynomial, generate_polynomial};
+use crate::share::{Share, check_parameters, new_set_id, validate_shares};
+
+/// Number of secret bytes packed into one field element.
+/// One byte less than `Fp255::BYTES` so every chunk is below the modulus.
+const CHUNK_BYTES: usize = Fp255::BYTES - 1;
+
+/// Marks the end of the secret inside the last chunk (ISO/IEC 7816-4 padding)
+const PADDING_MARKER: u8 = 0x80;
+
+impl Share {
+    /// Encodes a GF(2^8) share in the HashiCorp Vault layout: the y-bytes followed by the x-coordinate.
+    ///
+    /// `None` if the id is 0 or does not fit in a byte, i.e. the share does not come from `split_bytes_gf256`.
+    pub fn to_vault_bytes(&self) -> Option<Vec<u8>> {
+        let x = u8::try_from(self.id).ok().filter(|&x| x != 0)?;
+        let mut bytes = self.value.clone();
+        bytes.push(x);
+        Some(bytes)
+    }
+
+    /// Decodes a share in the HashiCorp Vault layout, `None` for a malformed share.
+    ///
+    /// The layout carries neither the threshold nor a share set id: the caller provides the threshold,
+    /// and all Vault shares get set id 0.
+    pub fn from_vault_bytes(bytes: &[u8], threshold: u32) -> Option<Self> {
+        let (&x, value) = bytes.split_last()?;
+        if x == 0 || value.is_empty() {
+            return None;
+        }
+        Some(Self {
+            id: x as u32,
+            value: value.to_vec(),
+            threshold,
+            set_id: 0,
+        })
+    }
+}
+
+/// Splits an arbitrary-length secret into `total_shares` shares, any `threshold` of which recover it.
+///
+/// ```md
+/// secret || 0x80 || 0x00... = chunk_1 || chunk_2 || ... || chunk_m, with |chunk_k| = 31 bytes
+///
+/// each chunk is shared with its own polynomial f_k(x) over Fp255, party i receives
+///
+/// share_i = f_1(i) || f_2(i) || ... || f_m(i)
+/// ```
+pub fn split_bytes(
+    secret: &[u8],
+    threshold: u32,
+    total_shares: u32,
+) -> Result<Vec<Share>, ShamirError> {
+    check_parameters(threshold, total_shares)?;
+
+    let mut padded = secret.to_vec();
+    padded.push(PADDING_MARKER);
+    padded.resize(padded.len().div_ceil(CHUNK_BYTES) * CHUNK_BYTES, 0);
+
+    let polynomials: Vec<Vec<Fp255>> = padded
+        .chunks(CHUNK_BYTES)
+        .map(|chunk| generate_polynomial(chunk_to_field(chunk), threshold))
+        .collect::<Result<_, _>>()?;
+
+    let set_id = new_set_id();
+    Ok((1..=total_shares)
+        .map(|id| {
+            let x = Fp255::from_u64(id as u64);
+            let value = polynomials
+                .iter()
+                .flat_map(|polynomial| evaluate_polynomial(polynomial, x).to_bytes())
+                .collect();
+            Share {
+                id,
+                value,
+                threshold,
+                set_id,
+            }
+        })
+        .collect())
+}
+
+/// Recombines byte-string shares produced by `split_bytes`.
+pub fn combine_bytes(shares: &[Share]) -> Result<Vec<u8>, ShamirError> {
+    validate_shares(shares)?;
+    let len = common_length(shares)?;
+    if len % Fp255::BYTES != 0 {
+        return Err(ShamirError::InvalidShareValue(shares[0].id));
+    }
+
+    let ids: Vec<u32> = shares.iter().map(|share| share.id).collect();
+    let mut padded = Vec::with_capacity(len / Fp255::BYTES * CHUNK_BYTES);
+    for offset in (0..len).step_by(Fp255::BYTES) {
+        let values = shares
+            .iter()
+            .map(|share| {
+                Fp255::from_bytes(&share.value[offset..offset + Fp255::BYTES])
+                    .ok_or(ShamirError::InvalidShareValue(share.id))
+            })
+            .collect::<Result<Vec<Fp255>, ShamirError>>()?;
+
+        let chunk = interpolate_at_zero(&ids, values).to_bytes();
+        if chunk[CHUNK_BYTES] != 0 {
+            return Err(ShamirError::InvalidPadding);
+        }
+        padded.extend_from_slice(&chunk[..CHUNK_BYTES]);
+    }
+
+    // strip the 0x80 0x00... padding
+    while padded.last() == Some(&0) {
+        padded.pop();
+    }
+    if padded.pop() != Some(PADDING_MARKER) {
+        return Err(ShamirError::InvalidPadding);
+    }
+
+    Ok(padded)
+}
+
+/// Splits a secret byte by byte over GF(2^8), the scheme used by HashiCorp Vault.
+///
+/// ```md
+/// every byte s_k gets its own polynomial f_k(x) = s_k + a_1*x + ... + a_{t-1}*x^{t-1} over GF(2^8)
+///
+/// share_i = f_1(i) || f_2(i) || ... || f_m(i), so shares are as long as the secret
+/// ```
+///
+/// The x-coordinates are single bytes, hence at most 255 shares.
+pub fn split_bytes_gf256(
+    secret: &[u8],
+    threshold: u32,
+    total_shares: u32,
+) -> Result<Vec<Share>, ShamirError> {
+    check_parameters(threshold, total_shares)?;
+    if total_shares > 255 {
+        // x = 256 does not fit in a byte
+        return Err(ShamirError::InvalidShareId(256));
+    }
+
+    let mut rng = rand::rng();
+    let set_id = new_set_id();
+    let mut shares: Vec<Share> = (1..=total_shares)
+        .map(|id| Share {
+            id,
+            value: Vec::with_capacity(secret.len()),
+            threshold,
+            set_id,
+        })
+        .collect();
+
+    let mut coefficients = vec![0u8; threshold as usize];
+    for &byte in secret {
+        coefficients[0] = byte;
+        rng.fill_bytes(&mut coefficients[1..]);
+
+        for share in shares.iter_mut() {
+            let y = evaluate_gf256(&coefficients, share.id as u8);
+            share.value.push(y);
+        }
+    }
+
+    Ok(shares)
+}
+
+/// Recombines shares produced by `split_bytes_gf256` (or decoded from Vault with `Share::from_vault_bytes`).
+pub fn combine_bytes_gf256(shares: &[Share]) -> Result<Vec<u8>, ShamirError> {
+    validate_shares(shares)?;
+    if let Some(share) = shares.iter().find(|share| share.id > 255) {
+        return Err(ShamirError::InvalidShareId(share.id));
+    }
+    common_length(shares)?;
+
+    let points: Vec<(u8, &[u8])> = shares
+        .iter()
+        .map(|share| (share.id as u8, share.value.as_slice()))
+        .collect();
+    Ok(interpolate(&points, 0))
+}
+
+/// All shares of one secret have the same length
+fn common_length(shares: &[Share]) -> Result<usize, ShamirError> {
+    let len = shares[0].value.len();
+    match shares.iter().find(|share| share.value.len() != len) {
+        Some(share) => Err(ShamirError::InvalidShareValue(share.id)),
+        None => Ok(len),
+    }
+}
+
+// Horner's rule: f(x) = a_0 + x*(a_1 + x*(a_2 + ...))
+fn evaluate_gf256(coefficients: &[u8], x: u8) -> u8 {
+    coefficients
+        .iter()
+        .rev()
+        .fold(0u8, |acc, &coeff| gf_add(gf_mul_ct(acc, x), coeff))
+}
+
+fn chunk_to_field(chunk: &[u8]) -> Fp255 {
+    let mut bytes = [0u8; Fp255::BYTES];
+    bytes[..chunk.len()].copy_from_slice(chunk);
+    Fp255::from_bytes(&bytes).expect("a 31-byte chunk is below the modulus")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_and_combine_bytes() {
+        let secrets: [&[u8]; 5] = [
+            b"",
+            b"correct horse battery staple",
+            &[0u8; 32],
+            &[0xff; 64],
+            &[0x80; 100],
+        ];
+
+        for secret in secrets {
+            let shares = split_bytes(secret, 3, 5).unwrap();
+            assert_eq!(shares.len(), 5);
+
+            let combined = combine_bytes(&shares[1..4]).unwrap();
+            assert_eq!(combined, secret);
+        }
+    }
+
+    #[test]
+    fn test_share_length_follows_secret_length() {
+        // 31 bytes plus the padding marker spill into a second chunk
+        let shares = split_bytes(&[7u8; 31], 2, 3).unwrap();
+        assert!(
+            shares
+                .iter()
+                .all(|share| share.value.len() == 2 * Fp255::BYTES)
+        );
+
+        let shares = split_bytes(&[7u8; 30], 2, 3).unwrap();
+        assert!(shares.iter().all(|share| share.value.len() == Fp255::BYTES));
+    }
+
+    #[test]
+    fn test_combine_rejects_bad_shares() {
+        let secret = b"seed phrase that must stay secret";
+        let shares = split_bytes(secret, 3, 5).unwrap();
+        assert_eq!(
+            combine_bytes(&shares[..2]),
+            Err(ShamirError::InsufficientShares {
+                got: 2,
+                threshold: 3
+            })
+        );
+
+        let mut truncated = shares[..3].to_vec();
+        truncated[2].value.pop();
+        assert_eq!(
+            combine_bytes(&truncated),
+            Err(ShamirError::InvalidShareValue(3))
+        );
+
+        let mut out_of_field = shares[..3].to_vec();
+        out_of_field[1].value[..Fp255::BYTES].fill(0xff);
+        assert_eq!(
+            combine_bytes(&out_of_field),
+            Err(ShamirError::InvalidShareValue(2))
+        );
+
+        // a tampered share shifts the last chunk by li(0) * 2^240, right into the padding
+        let mut tampered = shares[..3].to_vec();
+        tampered[0].value[Fp255::BYTES + 30] ^= 1;
+        assert_eq!(combine_bytes(&tampered), Err(ShamirError::InvalidPadding));
+    }
+
+    #[test]
+    fn test_split_rejects_invalid_parameters() {
+        assert_eq!(
+            split_bytes(b"secret", 0, 3),
+            Err(ShamirError::InvalidThreshold {
+                threshold: 0,
+                total_shares: 3
+            })
+        );
+        assert_eq!(
+            split_bytes_gf256(b"secret", 4, 3),
+            Err(ShamirError::InvalidThreshold {
+                threshold: 4,
+                total_shares: 3
+            })
+        );
+        assert_eq!(
+            split_bytes_gf256(b"secret", 2, 256),
+            Err(ShamirError::InvalidShareId(256))
+        );
+    }
+
+    #[test]
+    fn test_split_and_combine_gf256() {
+        let secret = b"correct horse battery staple";
+        let shares = split_bytes_gf256(secret, 3, 5).unwrap();
+        assert!(shares.iter().all(|share| share.value.len() == secret.len()));
+
+        assert_eq!(combine_bytes_gf256(&shares[2..]).unwrap(), secret);
+        assert_eq!(combine_bytes_gf256(&shares).unwrap(), secret);
+        assert!(combine_bytes_gf256(&shares[..2]).is_err());
+    }
+
+    #[test]
+    fn test_vault_layout_round_trip() {
+        let shares = split_bytes_gf256(&[0xde, 0xad, 0xbe, 0xef], 2, 3).unwrap();
+        let parts: Vec<Vec<u8>> = shares
+            .iter()
+            .map(|share| share.to_vault_bytes().unwrap())
+            .collect();
+        assert_eq!(parts[1], [&shares[1].value[..], &[2]].concat());
+
+        let decoded: Vec<Share> = parts
+            .iter()
+            .map(|part| Share::from_vault_bytes(part, 2).unwrap())
+            .collect();
+        assert!(
+            decoded
+                .iter()
+                .zip(&shares)
+                .all(|(decoded, share)| { decoded.id == share.id && decoded.value == share.value })
+        );
+        assert_eq!(
+            combine_bytes_gf256(&decoded[1..]).unwrap(),
+            [0xde, 0xad, 0xbe, 0xef]
+        );
+
+        assert_eq!(Share::from_vault_bytes(&[], 2), None);
+        assert_eq!(Share::from_vault_bytes(&[0x42], 2), None);
+        assert_eq!(Share::from_vault_bytes(&[0x42, 0], 2), None);
+
+        for id in [0, 256] {
+            let share = Share {
+                id,
+                ..shares[0].clone()
+            };
+            assert_eq!(share.to_vault_bytes(), None);
+        }
+    }
+
+    #[test]
+    fn test_combine_vault_parts() {
+        // f(x) = s + 0x57*x, evaluated with the FIPS-197 products 0x57*0x83 = 0xc1 and 0x57*0x13 = 0xfe,
+        // laid out as Vault stores them: y || x
+        let secret = 0x2a;
+        let parts = [[secret ^ 0xc1, 0x83], [secret ^ 0xfe, 0x13]];
+
+        let shares: Vec<Share> = parts
+            .iter()
+            .map(|part| Share::from_vault_bytes(part, 2).unwrap())
+            .collect();
+        assert_eq!(combine_bytes_gf256(&shares).unwrap(), [secret]);
+    }
+}
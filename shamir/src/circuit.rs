@@ -0,0 +1,348 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::arithmetic::{
+    add_constant, add_shares, bgw_multiply, multiply_by_constant, product_threshold,
+};
+use crate::errors::ShamirError;
+use crate::ff_ops::PrimeField;
+use crate::share::{Share, check_parameters};
+use crate::{generate_shares, reconstruct_secret};
+
+/// A value in a circuit, returned by the `Circuit` method that computes it.
+/// It belongs to that circuit, other circuits reject it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Wire {
+    circuit: u64,
+    index: usize,
+}
+
+static NEXT_CIRCUIT_ID: AtomicU64 = AtomicU64::new(0);
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Gate<F> {
+    /// The next private input
+    Input,
+    Add(Wire, Wire),
+    Mul(Wire, Wire),
+    AddConstant(Wire, F),
+    MulConstant(Wire, F),
+}
+
+/// An arithmetic circuit over F, built gate by gate:
+///
+/// ```text
+/// let mut circuit = Circuit::new();
+/// let (x, y) = (circuit.input(), circuit.input());
+/// let xy = circuit.mul(x, y);
+/// let out = circuit.add_constant(xy, F::from_u64(1));
+/// circuit.output(out); // x * y + 1
+/// ```
+///
+/// The gate methods and `output` panic when given a wire of another circuit. Circuits are not
+/// `Clone`: two copies would accept each other's wires while holding different gates behind them.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Circuit<F> {
+    id: u64,
+    gates: Vec<Gate<F>>,
+    outputs: Vec<Wire>,
+}
+
+impl<F: PrimeField> Default for Circuit<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: PrimeField> Circuit<F> {
+    pub fn new() -> Self {
+        Circuit {
+            id: NEXT_CIRCUIT_ID.fetch_add(1, Ordering::Relaxed),
+            gates: Vec::new(),
+            outputs: Vec::new(),
+        }
+    }
+
+    pub fn input(&mut self) -> Wire {
+        self.push(Gate::Input)
+    }
+
+    /// Panics on a wire of another circuit, as do the other gate methods
+    pub fn add(&mut self, a: Wire, b: Wire) -> Wire {
+        self.push(Gate::Add(a, b))
+    }
+
+    pub fn mul(&mut self, a: Wire, b: Wire) -> Wire {
+        self.push(Gate::Mul(a, b))
+    }
+
+    pub fn add_constant(&mut self, wire: Wire, constant: F) -> Wire {
+        self.push(Gate::AddConstant(wire, constant))
+    }
+
+    pub fn mul_constant(&mut self, wire: Wire, constant: F) -> Wire {
+        self.push(Gate::MulConstant(wire, constant))
+    }
+
+    /// Reveals the wire at the end of the computation. Panics on a wire of another circuit.
+    pub fn output(&mut self, wire: Wire) {
+        self.check_wire(wire);
+        self.outputs.push(wire);
+    }
+
+    /// Number of private inputs, in the order they were added
+    pub fn inputs(&self) -> usize {
+        self.gates
+            .iter()
+            .filter(|gate| matches!(gate, Gate::Input))
+            .count()
+    }
+
+    /// Number of multiplication gates, the only ones that need interaction
+    pub fn multiplications(&self) -> usize {
+        self.gates
+            .iter()
+            .filter(|gate| matches!(gate, Gate::Mul(..)))
+            .count()
+    }
+
+    /// Evaluates the circuit in the clear, the reference for `Simulator::run`
+    pub fn evaluate(&self, inputs: &[F]) -> Result<Vec<F>, ShamirError> {
+        self.evaluate_with(
+            inputs,
+            |&input| Ok(input),
+            |a, b| Ok(*a + *b),
+            |a, b| Ok(*a * *b),
+            |a, c| *a + c,
+            |a, c| *a * c,
+            |value| Ok(*value),
+        )
+    }
+
+    /// Runs the gates in order on any representation of the values
+    #[allow(clippy::too_many_arguments)]
+    fn evaluate_with<V>(
+        &self,
+        inputs: &[F],
+        mut input: impl FnMut(&F) -> Result<V, ShamirError>,
+        add: impl Fn(&V, &V) -> Result<V, ShamirError>,
+        mul: impl Fn(&V, &V) -> Result<V, ShamirError>,
+        add_constant: impl Fn(&V, F) -> V,
+        mul_constant: impl Fn(&V, F) -> V,
+        open: impl Fn(&V) -> Result<F, ShamirError>,
+    ) -> Result<Vec<F>, ShamirError> {
+        if inputs.len() != self.inputs() {
+            return Err(ShamirError::InvalidInputCount {
+                expected: self.inputs(),
+                got: inputs.len(),
+            });
+        }
+
+        let mut inputs = inputs.iter();
+        let mut wires: Vec<V> = Vec::with_capacity(self.gates.len());
+        for gate in &self.gates {
+            let value = match gate {
+                Gate::Input => input(inputs.next().expect("input count checked above"))?,
+                Gate::Add(a, b) => add(&wires[a.index], &wires[b.index])?,
+                Gate::Mul(a, b) => mul(&wires[a.index], &wires[b.index])?,
+                Gate::AddConstant(a, c) => add_constant(&wires[a.index], *c),
+                Gate::MulConstant(a, c) => mul_constant(&wires[a.index], *c),
+            };
+            wires.push(value);
+        }
+
+        self.outputs
+            .iter()
+            .map(|wire| open(&wires[wire.index]))
+            .collect()
+    }
+
+    fn push(&mut self, gate: Gate<F>) -> Wire {
+        match &gate {
+            Gate::Input => {}
+            Gate::Add(a, b) | Gate::Mul(a, b) => {
+                self.check_wire(*a);
+                self.check_wire(*b);
+            }
+            Gate::AddConstant(a, _) | Gate::MulConstant(a, _) => self.check_wire(*a),
+        }
+        self.gates.push(gate);
+        Wire {
+            circuit: self.id,
+            index: self.gates.len() - 1,
+        }
+    }
+
+    fn check_wire(&self, wire: Wire) {
+        assert!(
+            wire.circuit == self.id && wire.index < self.gates.len(),
+            "wire of another circuit"
+        );
+    }
+}
+
+/// Local multi-party simulator: `parties` parties evaluate a circuit on Shamir-shared inputs
+/// with threshold `threshold`
+///
+/// Every input is shared by its owner, additions and constants are computed locally on the shares,
+/// every multiplication is a BGW round with degree reduction, and the outputs are opened at the end.
+/// Multiplications need n >= 2t - 1.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Simulator {
+    pub threshold: u32,
+    pub parties: u32,
+}
+
+impl Simulator {
+    pub fn run<F: PrimeField>(
+        &self,
+        circuit: &Circuit<F>,
+        inputs: &[F],
+    ) -> Result<Vec<F>, ShamirError> {
+        check_parameters(self.threshold, self.parties)?;
+        if circuit.multiplications() > 0 {
+            check_parameters(
+                product_threshold(self.threshold, self.threshold)?,
+                self.parties,
+            )?;
+        }
+
+        // one share per party for every wire
+        circuit.evaluate_with(
+            inputs,
+            |&input| generate_shares(input, self.threshold, self.parties),
+            |a: &Vec<Share<F>>, b| a.iter().zip(b).map(|(a, b)| add_shares(a, b)).collect(),
+            |a, b| bgw_multiply(a, b),
+            |a, c| a.iter().map(|share| add_constant(share, c)).collect(),
+            |a, c| {
+                a.iter()
+                    .map(|share| multiply_by_constant(share, c))
+                    .collect()
+            },
+            |shares| reconstruct_secret(shares),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    use super::*;
+    use crate::{Fp, Fp255};
+
+    const PRIME: u64 = 997;
+
+    /// (x + y) * z + 3xy - 5, and x^4
+    fn example<F: PrimeField>() -> Circuit<F> {
+        let mut circuit = Circuit::new();
+        let (x, y, z) = (circuit.input(), circuit.input(), circuit.input());
+
+        let sum = circuit.add(x, y);
+        let left = circuit.mul(sum, z);
+        let xy = circuit.mul(x, y);
+        let right = circuit.mul_constant(xy, F::from_u64(3));
+        let total = circuit.add(left, right);
+        let out = circuit.add_constant(total, -F::from_u64(5));
+        circuit.output(out);
+
+        let square = circuit.mul(x, x);
+        let fourth = circuit.mul(square, square);
+        circuit.output(fourth);
+        circuit
+    }
+
+    #[test]
+    fn test_cleartext_evaluation() {
+        type F = Fp<PRIME>;
+        let circuit = example::<F>();
+        assert_eq!(circuit.inputs(), 3);
+        assert_eq!(circuit.multiplications(), 4);
+
+        let inputs = [F::from_u64(2), F::from_u64(3), F::from_u64(4)];
+        // (2 + 3) * 4 + 18 - 5 = 33, 2^4 = 16
+        assert_eq!(
+            circuit.evaluate(&inputs),
+            Ok(vec![F::from_u64(33), F::from_u64(16)])
+        );
+    }
+
+    #[test]
+    fn test_simulation_matches_cleartext() {
+        let mut rng = StdRng::seed_from_u64(20);
+        let circuit = example::<Fp255>();
+
+        for (threshold, parties) in [(1, 1), (2, 3), (3, 5), (3, 7)] {
+            let simulator = Simulator { threshold, parties };
+            let inputs: Vec<Fp255> = (0..3).map(|_| Fp255::random(&mut rng)).collect();
+            assert_eq!(simulator.run(&circuit, &inputs), circuit.evaluate(&inputs));
+        }
+    }
+
+    #[test]
+    fn test_small_field_wraps_around() {
+        type F = Fp<PRIME>;
+        let circuit = example::<F>();
+        let simulator = Simulator {
+            threshold: 2,
+            parties: 4,
+        };
+
+        for inputs in [[0, 0, 0], [996, 996, 996], [500, 600, 700]] {
+            let inputs = inputs.map(F::from_u64);
+            assert_eq!(simulator.run(&circuit, &inputs), circuit.evaluate(&inputs));
+        }
+    }
+
+    #[test]
+    fn test_invalid_runs_are_rejected() {
+        type F = Fp<PRIME>;
+        let circuit = example::<F>();
+
+        // products of 3-of-4 shares have degree 4, beyond what 4 parties can reduce
+        let simulator = Simulator {
+            threshold: 3,
+            parties: 4,
+        };
+        let inputs = [F::one(); 3];
+        assert_eq!(
+            simulator.run(&circuit, &inputs),
+            Err(ShamirError::InvalidThreshold {
+                threshold: 5,
+                total_shares: 4
+            })
+        );
+
+        // without multiplications any threshold works
+        let mut linear = Circuit::new();
+        let x = linear.input();
+        let y = linear.mul_constant(x, F::from_u64(2));
+        linear.output(y);
+        assert_eq!(
+            simulator.run(&linear, &[F::from_u64(21)]),
+            Ok(vec![F::from_u64(42)])
+        );
+
+        assert_eq!(
+            linear.evaluate(&inputs),
+            Err(ShamirError::InvalidInputCount {
+                expected: 1,
+                got: 3
+            })
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "wire of another circuit")]
+    fn test_foreign_wire_is_rejected() {
+        type F = Fp<PRIME>;
+        let mut circuit = Circuit::<F>::new();
+        let x = circuit.input();
+        circuit.input();
+
+        // gate 0 exists in both circuits, but is another value
+        let mut other = Circuit::<F>::new();
+        let y = other.input();
+        circuit.mul(x, y);
+    }
+}
@@ -0,0 +1,338 @@
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use sha2::{Digest, Sha256};
+
+use crate::errors::ShamirError;
+use crate::ff_ops::{FieldId, PrimeField};
+use crate::share::Share;
+
+/// ```md
+/// Binary share format, version 1, integers big-endian:
+///
+/// magic     2 bytes   "SS"
+/// version   1 byte    0x01
+/// field     1 byte    0x01 Fp<P>, 0x02 Fp255, 0x03 Fp255 byte chunks, 0x04 GF(2^8) bytes
+/// modulus   8 bytes   P, only for Fp<P>
+/// id        4 bytes
+/// threshold 4 bytes
+/// set id    8 bytes
+/// length    4 bytes   length of the value
+/// value     length bytes
+/// checksum  4 bytes   first 4 bytes of SHA-256 over everything above
+/// ```
+const MAGIC: &[u8; 2] = b"SS";
+pub const VERSION: u8 = 1;
+const CHECKSUM_BYTES: usize = 4;
+
+/// Encodes a share of a field element
+pub fn encode_share<F: PrimeField>(share: &Share<F>) -> Vec<u8> {
+    encode(
+        F::FIELD_ID,
+        share.id,
+        share.threshold,
+        share.set_id,
+        &share.value.to_bytes(),
+    )
+}
+
+/// Decodes a share of a field element, checking the checksum and that it belongs to F
+pub fn decode_share<F: PrimeField>(bytes: &[u8]) -> Result<Share<F>, ShamirError> {
+    let (field, share) = decode(bytes)?;
+    if field != F::FIELD_ID {
+        return Err(ShamirError::FieldMismatch);
+    }
+    let value = F::from_bytes(&share.value).ok_or(ShamirError::InvalidShareValue(share.id))?;
+
+    Ok(Share {
+        id: share.id,
+        value,
+        threshold: share.threshold,
+        set_id: share.set_id,
+    })
+}
+
+/// The schemes that share byte strings
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ByteScheme {
+    /// `split_bytes`: 31-byte chunks over `Fp255`
+    Fp255,
+    /// `split_bytes_gf256`: byte by byte over GF(2^8)
+    Gf256,
+}
+
+impl From<ByteScheme> for FieldId {
+    fn from(scheme: ByteScheme) -> Self {
+        match scheme {
+            ByteScheme::Fp255 => FieldId::Fp255Bytes,
+            ByteScheme::Gf256 => FieldId::Gf256Bytes,
+        }
+    }
+}
+
+/// Encodes a share of a byte string, `scheme` tells which one produced it
+pub fn encode_byte_share(share: &Share, scheme: ByteScheme) -> Vec<u8> {
+    encode(
+        scheme.into(),
+        share.id,
+        share.threshold,
+        share.set_id,
+        &share.value,
+    )
+}
+
+/// Decodes a share of a byte string together with the scheme that produced it
+pub fn decode_byte_share(bytes: &[u8]) -> Result<(ByteScheme, Share), ShamirError> {
+    let (field, share) = decode(bytes)?;
+    match field {
+        FieldId::Fp255Bytes => Ok((ByteScheme::Fp255, share)),
+        FieldId::Gf256Bytes => Ok((ByteScheme::Gf256, share)),
+        _ => Err(ShamirError::FieldMismatch),
+    }
+}
+
+/// Lowercase hex, for printing a share
+pub fn to_hex(encoded: &[u8]) -> String {
+    hex::encode(encoded)
+}
+
+/// Parses a hex share, ignoring whitespace and dashes operators may have typed to group digits
+pub fn from_hex(text: &str) -> Result<Vec<u8>, ShamirError> {
+    let digits: String = text
+        .chars()
+        .filter(|c| !c.is_whitespace() && *c != '-')
+        .collect();
+    hex::decode(digits).map_err(|_| ShamirError::InvalidEncoding)
+}
+
+/// Standard padded base64
+pub fn to_base64(encoded: &[u8]) -> String {
+    STANDARD.encode(encoded)
+}
+
+pub fn from_base64(text: &str) -> Result<Vec<u8>, ShamirError> {
+    let text: String = text.chars().filter(|c| !c.is_whitespace()).collect();
+    STANDARD
+        .decode(text)
+        .map_err(|_| ShamirError::InvalidEncoding)
+}
+
+fn encode(field: FieldId, id: u32, threshold: u32, set_id: u64, value: &[u8]) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(44 + value.len());
+    bytes.extend_from_slice(MAGIC);
+    bytes.push(VERSION);
+    match field {
+        FieldId::Fp(modulus) => {
+            bytes.push(0x01);
+            bytes.extend_from_slice(&modulus.to_be_bytes());
+        }
+        FieldId::Fp255 => bytes.push(0x02),
+        FieldId::Fp255Bytes => bytes.push(0x03),
+        FieldId::Gf256Bytes => bytes.push(0x04),
+    }
+    bytes.extend_from_slice(&id.to_be_bytes());
+    bytes.extend_from_slice(&threshold.to_be_bytes());
+    bytes.extend_from_slice(&set_id.to_be_bytes());
+    bytes.extend_from_slice(&(value.len() as u32).to_be_bytes());
+    bytes.extend_from_slice(value);
+
+    let checksum = checksum(&bytes);
+    bytes.extend_from_slice(&checksum);
+    bytes
+}
+
+fn decode(bytes: &[u8]) -> Result<(FieldId, Share), ShamirError> {
+    if bytes.len() < CHECKSUM_BYTES {
+        return Err(ShamirError::InvalidEncoding);
+    }
+    let (body, expected) = bytes.split_at(bytes.len() - CHECKSUM_BYTES);
+    if checksum(body) != expected {
+        return Err(ShamirError::ChecksumMismatch);
+    }
+
+    let mut reader = Reader(body);
+    if reader.take(2)? != MAGIC {
+        return Err(ShamirError::InvalidEncoding);
+    }
+    let version = reader.take(1)?[0];
+    if version != VERSION {
+        return Err(ShamirError::UnsupportedVersion(version));
+    }
+    let field = match reader.take(1)?[0] {
+        0x01 => FieldId::Fp(u64::from_be_bytes(reader.array()?)),
+        0x02 => FieldId::Fp255,
+        0x03 => FieldId::Fp255Bytes,
+        0x04 => FieldId::Gf256Bytes,
+        _ => return Err(ShamirError::InvalidEncoding),
+    };
+    let id = u32::from_be_bytes(reader.array()?);
+    let threshold = u32::from_be_bytes(reader.array()?);
+    let set_id = u64::from_be_bytes(reader.array()?);
+    let len = u32::from_be_bytes(reader.array()?) as usize;
+    let value = reader.take(len)?.to_vec();
+    if !reader.0.is_empty() {
+        return Err(ShamirError::InvalidEncoding);
+    }
+
+    Ok((
+        field,
+        Share {
+            id,
+            value,
+            threshold,
+            set_id,
+        },
+    ))
+}
+
+fn checksum(bytes: &[u8]) -> [u8; CHECKSUM_BYTES] {
+    let digest = Sha256::digest(bytes);
+    let mut checksum = [0u8; CHECKSUM_BYTES];
+    checksum.copy_from_slice(&digest[..CHECKSUM_BYTES]);
+    checksum
+}
+
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], ShamirError> {
+        if self.0.len() < len {
+            return Err(ShamirError::InvalidEncoding);
+        }
+        let (head, tail) = self.0.split_at(len);
+        self.0 = tail;
+        Ok(head)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], ShamirError> {
+        let mut array = [0u8; N];
+        array.copy_from_slice(self.take(N)?);
+        Ok(array)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ff_ops::{Fp64, Fp255};
+    use crate::{
+        combine_bytes, combine_bytes_gf256, generate_shares, reconstruct_secret, split_bytes,
+        split_bytes_gf256,
+    };
+
+    #[test]
+    fn test_field_share_round_trip() {
+        let secret = Fp255::from_u64(0x5eed);
+        let shares = generate_shares(secret, 2, 3).unwrap();
+
+        let decoded: Vec<Share<Fp255>> = shares
+            .iter()
+            .map(|share| decode_share(&encode_share(share)).unwrap())
+            .collect();
+        assert_eq!(decoded, shares);
+        assert_eq!(reconstruct_secret(&decoded[1..]), Ok(secret));
+
+        let shares = generate_shares(Fp64::from_u64(7), 2, 3).unwrap();
+        let encoded = encode_share(&shares[0]);
+        assert_eq!(encoded.len(), 4 + 8 + 20 + 8 + 4);
+        assert_eq!(decode_share::<Fp64>(&encoded), Ok(shares[0].clone()));
+    }
+
+    #[test]
+    fn test_byte_share_round_trip() {
+        let secret = b"correct horse battery staple";
+
+        let shares = split_bytes(secret, 2, 3).unwrap();
+        let decoded: Vec<Share> = shares
+            .iter()
+            .map(|share| {
+                let (scheme, share) =
+                    decode_byte_share(&encode_byte_share(share, ByteScheme::Fp255)).unwrap();
+                assert_eq!(scheme, ByteScheme::Fp255);
+                share
+            })
+            .collect();
+        assert_eq!(combine_bytes(&decoded[..2]).unwrap(), secret);
+
+        let shares = split_bytes_gf256(secret, 2, 3).unwrap();
+        let encoded = encode_byte_share(&shares[2], ByteScheme::Gf256);
+        let (scheme, decoded) = decode_byte_share(&encoded).unwrap();
+        assert_eq!(scheme, ByteScheme::Gf256);
+        assert_eq!(
+            combine_bytes_gf256(&[shares[0].clone(), decoded]).unwrap(),
+            secret
+        );
+    }
+
+    #[test]
+    fn test_text_round_trip() {
+        let shares = generate_shares(Fp255::from_u64(0x5eed), 2, 3).unwrap();
+        let encoded = encode_share(&shares[0]);
+
+        let hex = to_hex(&encoded);
+        assert!(hex.starts_with("535301"));
+        assert_eq!(from_hex(&hex), Ok(encoded.clone()));
+
+        // operators may group the digits when copying a share by hand
+        let grouped: Vec<String> = hex
+            .as_bytes()
+            .chunks(4)
+            .map(|group| String::from_utf8(group.to_vec()).unwrap())
+            .collect();
+        assert_eq!(from_hex(&grouped.join("-")), Ok(encoded.clone()));
+        assert_eq!(
+            from_hex(&grouped.join(" ").to_uppercase()),
+            Ok(encoded.clone())
+        );
+
+        let base64 = to_base64(&encoded);
+        assert_eq!(from_base64(&base64), Ok(encoded.clone()));
+        assert_eq!(from_hex("53zz"), Err(ShamirError::InvalidEncoding));
+        assert_eq!(from_base64("U1M*"), Err(ShamirError::InvalidEncoding));
+    }
+
+    #[test]
+    fn test_corruption_is_detected() {
+        let shares = generate_shares(Fp255::from_u64(0x5eed), 2, 3).unwrap();
+        let encoded = encode_share(&shares[1]);
+
+        for position in 0..encoded.len() {
+            for bit in 0..8 {
+                let mut corrupted = encoded.clone();
+                corrupted[position] ^= 1 << bit;
+                assert_eq!(
+                    decode_share::<Fp255>(&corrupted),
+                    Err(ShamirError::ChecksumMismatch)
+                );
+            }
+        }
+
+        assert_eq!(
+            decode_share::<Fp255>(&encoded[..encoded.len() - 1]),
+            Err(ShamirError::ChecksumMismatch)
+        );
+        assert_eq!(
+            decode_share::<Fp255>(&encoded[..3]),
+            Err(ShamirError::InvalidEncoding)
+        );
+    }
+
+    #[test]
+    fn test_wrong_field_or_version_is_rejected() {
+        let shares = generate_shares(Fp255::from_u64(1), 2, 3).unwrap();
+        let encoded = encode_share(&shares[0]);
+        assert_eq!(
+            decode_share::<Fp64>(&encoded),
+            Err(ShamirError::FieldMismatch)
+        );
+        assert_eq!(decode_byte_share(&encoded), Err(ShamirError::FieldMismatch));
+
+        let mut future = encoded[..encoded.len() - CHECKSUM_BYTES].to_vec();
+        future[2] = 2;
+        let checksum = checksum(&future);
+        future.extend_from_slice(&checksum);
+        assert_eq!(
+            decode_share::<Fp255>(&future),
+            Err(ShamirError::UnsupportedVersion(2))
+        );
+    }
+}
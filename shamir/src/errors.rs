@@ -0,0 +1,73 @@
+use thiserror::Error;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum ShamirError {
+    #[error("Invalid threshold {threshold} for {total_shares} shares")]
+    InvalidThreshold { threshold: u32, total_shares: u32 },
+
+    #[error("Insufficient shares: got {got}, need {threshold}")]
+    InsufficientShares { got: usize, threshold: u32 },
+
+    #[error("Invalid share id: {0}")]
+    InvalidShareId(u32),
+
+    #[error("Duplicate share id: {0}")]
+    DuplicateShareId(u32),
+
+    #[error("Shares belong to different share sets")]
+    MismatchedShares,
+
+    #[error("Sharing polynomial has {sharing} coefficients, blinding polynomial {blinding}")]
+    PolynomialLengthMismatch { sharing: usize, blinding: usize },
+
+    #[error("Invalid value in share {0}")]
+    InvalidShareValue(u32),
+
+    #[error("Invalid padding in the reconstructed secret")]
+    InvalidPadding,
+
+    #[error("Too many corrupted shares to recover the secret")]
+    TooManyErrors,
+
+    #[error("Malformed share encoding")]
+    InvalidEncoding,
+
+    #[error("Share checksum does not match")]
+    ChecksumMismatch,
+
+    #[error("Unsupported share encoding version {0}")]
+    UnsupportedVersion(u8),
+
+    #[error("Share belongs to another field")]
+    FieldMismatch,
+
+    #[error("Unknown word in mnemonic: {0}")]
+    UnknownWord(String),
+
+    #[error("Invalid master secret length: {0} bytes")]
+    InvalidSecretLength(usize),
+
+    #[error("Invalid iteration exponent {0}")]
+    InvalidIterationExponent(u8),
+
+    #[error("Passphrase must be printable ASCII")]
+    InvalidPassphrase,
+
+    #[error("Shares do not match their digest")]
+    InvalidDigest,
+
+    #[error("Shares do not satisfy the access structure")]
+    UnauthorizedShares,
+
+    #[error("Share ids do not determine the secret in this field")]
+    DegenerateShares,
+
+    #[error("Invalid access policy: {0}")]
+    InvalidPolicy(String),
+
+    #[error("Circuit takes {expected} inputs, got {got}")]
+    InvalidInputCount { expected: usize, got: usize },
+
+    #[error("MAC check failed on an opened value")]
+    MacCheckFailed,
+}
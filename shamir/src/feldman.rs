@@ -0,0 +1,167 @@
+use ark_bls12_381::{G1Affine, G1Projective};
+use ark_ec::{CurveGroup, PrimeGroup};
+
+use crate::errors::ShamirError;
+use crate::ff_ops::{Fp255, PrimeField};
+use crate::polynomial::{evaluate_polynomial, generate_polynomial};
+use crate::share::{Share, check_parameters, new_set_id};
+
+/// ```md
+/// Feldman verifiable secret sharing
+///
+/// The dealer shares the secret s with f(x) = s + a_1*x + ... + a_{t-1}*x^{t-1} over Fp255 as usual,
+/// and additionally publishes a commitment to every coefficient in G1 of BLS12-381,
+/// whose order is the modulus of Fp255:
+///
+/// C_k = g^{a_k}, k = [0, t)
+///
+/// Party i can then check its share f(i) without learning anything else:
+///
+/// g^{f(i)} = g^{Σ(a_k * i^k)} = Π(C_k^{i^k})
+///
+/// A dealer handing out a share that is not on the committed polynomial gets caught.
+/// Note that C_0 = g^s is public: s is only computationally hidden (see `pedersen` for hiding commitments).
+/// ```
+pub fn commit_polynomial(coefficients: &[Fp255]) -> Vec<G1Affine> {
+    let generator = G1Projective::generator();
+    let commitments: Vec<G1Projective> = coefficients
+        .iter()
+        .map(|&coeff| generator * coeff)
+        .collect();
+    G1Projective::normalize_batch(&commitments)
+}
+
+/// Generates shares for all parties together with the commitments to the dealer's polynomial
+pub fn generate_shares(
+    secret: Fp255,
+    threshold: u32,
+    total_shares: u32,
+) -> Result<(Vec<Share<Fp255>>, Vec<G1Affine>), ShamirError> {
+    check_parameters(threshold, total_shares)?;
+    let polynomial = generate_polynomial(secret, threshold)?;
+    let commitments = commit_polynomial(&polynomial);
+
+    let set_id = new_set_id();
+    let shares = (1..=total_shares)
+        .map(|id| Share {
+            id,
+            value: evaluate_polynomial(&polynomial, Fp255::from_u64(id as u64)),
+            threshold,
+            set_id,
+        })
+        .collect();
+
+    Ok((shares, commitments))
+}
+
+/// Checks that `share` is the evaluation at `id` of the polynomial committed to by `commitments`,
+/// and that this polynomial has degree `threshold - 1`, the threshold the parties agreed on.
+///
+/// Without the degree check a dealer could commit to a higher-degree polynomial: every share
+/// would still verify, but different sets of `threshold` shares would reconstruct different secrets.
+pub fn verify_share(id: u32, share: Fp255, threshold: u32, commitments: &[G1Affine]) -> bool {
+    if commitments.len() != threshold as usize {
+        return false;
+    }
+    let lhs = G1Projective::generator() * share;
+    lhs == evaluate_commitments(id, commitments)
+}
+
+/// Π(C_k^{x^k}) = g^{f(x)}, the commitment to the share of party x
+pub fn evaluate_commitments(id: u32, commitments: &[G1Affine]) -> G1Projective {
+    let x = Fp255::from_u64(id as u64);
+    let mut result = G1Projective::default();
+    let mut power = Fp255::one();
+
+    for commitment in commitments {
+        result += *commitment * power;
+        power *= x;
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reconstruct_secret;
+
+    #[test]
+    fn test_honest_dealer() {
+        let secret = Fp255::from_u64(42);
+        let (shares, commitments) = generate_shares(secret, 3, 5).unwrap();
+        assert_eq!(commitments.len(), 3);
+        assert_eq!(
+            commitments[0],
+            (G1Projective::generator() * secret).into_affine()
+        );
+
+        for share in &shares {
+            assert!(verify_share(share.id, share.value, 3, &commitments));
+        }
+        assert_eq!(reconstruct_secret(&shares), Ok(secret));
+    }
+
+    #[test]
+    fn test_cheating_dealer_is_detected() {
+        let (mut shares, commitments) = generate_shares(Fp255::from_u64(42), 3, 5).unwrap();
+
+        // the dealer hands party 2 a value off the committed polynomial
+        shares[1].value += Fp255::one();
+        assert!(!verify_share(2, shares[1].value, 3, &commitments));
+
+        // or gives party 3 the share meant for party 4
+        assert!(!verify_share(3, shares[3].value, 3, &commitments));
+    }
+
+    #[test]
+    fn test_commitments_to_another_polynomial() {
+        let (shares, _) = generate_shares(Fp255::from_u64(42), 3, 5).unwrap();
+        let (_, other_commitments) = generate_shares(Fp255::from_u64(42), 3, 5).unwrap();
+        assert!(!verify_share(1, shares[0].value, 3, &other_commitments));
+    }
+
+    #[test]
+    fn test_higher_degree_dealer_is_detected() {
+        // the dealer of a 3-out-of-5 sharing uses a polynomial of degree 3 instead of 2
+        let polynomial = generate_polynomial(Fp255::from_u64(42), 4).unwrap();
+        let commitments = commit_polynomial(&polynomial);
+        let shares: Vec<Share<Fp255>> = (1..=5)
+            .map(|id| Share {
+                id,
+                value: evaluate_polynomial(&polynomial, Fp255::from_u64(id as u64)),
+                threshold: 3,
+                set_id: 0,
+            })
+            .collect();
+
+        // every share is on the committed polynomial, yet 3-subsets disagree on the secret
+        assert!(
+            shares
+                .iter()
+                .all(|share| evaluate_commitments(share.id, &commitments)
+                    == G1Projective::generator() * share.value)
+        );
+        assert_ne!(
+            reconstruct_secret(&shares[..3]),
+            reconstruct_secret(&shares[2..])
+        );
+        assert!(
+            shares
+                .iter()
+                .all(|share| !verify_share(share.id, share.value, 3, &commitments))
+        );
+        assert!(verify_share(1, shares[0].value, 4, &commitments));
+    }
+
+    #[test]
+    fn test_invalid_threshold() {
+        assert_eq!(
+            generate_shares(Fp255::from_u64(42), 0, 5),
+            Err(ShamirError::InvalidThreshold {
+                threshold: 0,
+                total_shares: 5
+            })
+        );
+    }
+}
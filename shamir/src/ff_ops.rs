@@ -1,83 +1,668 @@
-pub const PRIME: u64 = 997; // 2^64 - 5 // Miller-Rabin primality test
+use std::fmt::Debug;
+use std::hint::black_box;
+use std::ops::{Add, Mul, Neg, Sub};
 
-pub fn mod_add(a: u64, b: u64) -> u64 {
-    let sum = a as u128 + b as u128;
-    (sum % PRIME as u128) as u64
+use ark_ff::{BigInt, PrimeField as _};
+use rand::RngCore;
+
+/// The field a secret and its shares live in.
+///
+/// Shares are points (x, f(x)) on a random polynomial over this field, so the
+/// secret has to be an element of it: the modulus bounds what can be shared.
+pub trait PrimeField:
+    Copy
+    + Debug
+    + Eq
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Neg<Output = Self>
+{
+    /// Length of the canonical little-endian encoding in bytes
+    const BYTES: usize;
+
+    /// Identifies the field in serialized shares
+    const FIELD_ID: FieldId;
+
+    fn zero() -> Self;
+
+    fn one() -> Self;
+
+    fn from_u64(value: u64) -> Self;
+
+    /// Multiplicative inverse, `None` for zero
+    fn inverse(&self) -> Option<Self>;
+
+    /// Uniformly random element
+    fn random<R: RngCore + ?Sized>(rng: &mut R) -> Self;
+
+    /// Canonical little-endian encoding, always `BYTES` long
+    fn to_bytes(&self) -> Vec<u8>;
+
+    /// Inverse of `to_bytes`, `None` if the bytes do not encode a value below the modulus
+    fn from_bytes(bytes: &[u8]) -> Option<Self>;
+
+    fn is_zero(&self) -> bool {
+        *self == Self::zero()
+    }
 }
 
-pub fn mod_sub(a: u64, b: u64) -> u64 {
-    if a >= b { a - b } else { PRIME - (b - a) }
+/// ```md
+/// A prime field whose multiplicative group has a large power-of-two subgroup: p - 1 = 2^s * t, t odd.
+/// It contains the 2^k-th roots of unity for every k <= s, which radix-2 FFTs evaluate polynomials on.
+/// ```
+pub trait FftField: PrimeField {
+    /// s, the largest power of two dividing p - 1
+    const TWO_ADICITY: u32;
+
+    /// A primitive 2^s-th root of unity
+    fn two_adic_root_of_unity() -> Self;
+
+    /// A generator of the whole multiplicative group, which is outside every 2^k-subgroup
+    /// and shifts evaluation domains to cosets
+    fn multiplicative_generator() -> Self;
 }
 
-pub fn mod_mul(a: u64, b: u64) -> u64 {
-    let product = (a as u128) * (b as u128);
-    (product % PRIME as u128) as u64
+/// Inverts all values with a single field inversion (Montgomery's trick), `None` if one of them is zero
+///
+/// ```md
+/// prefix_i = v_0 * ... * v_i
+/// v_i^(-1) = prefix_{i-1} * prefix_i^(-1), and prefix_{i-1}^(-1) = prefix_i^(-1) * v_i
+/// ```
+pub fn batch_inverse<F: PrimeField>(values: &[F]) -> Option<Vec<F>> {
+    let mut prefix = Vec::with_capacity(values.len());
+    let mut product = F::one();
+    for &value in values {
+        prefix.push(product);
+        product = product * value;
+    }
+
+    let mut inverse = product.inverse()?;
+    let mut inverses = vec![F::zero(); values.len()];
+    for i in (0..values.len()).rev() {
+        inverses[i] = prefix[i] * inverse;
+        inverse = inverse * values[i];
+    }
+    Some(inverses)
 }
 
-// Eg, 2^3
-// 1/ result = 1, base = 2, exp = 3
-// exp is odd, result = result * base = 1 * 2 = 2
-// base = base * base = 2 * 2 = 4
-// exp = exp >> 1 = 3 >> 1 = 1
-// 2/ exp is odd, result = result * base = 2 * 4 = 8
-// base = base * base = 4 * 4 = 16
-// exp = exp >> 1 = 1 >> 1 = 0
-// 3/ exp is 0, return result = 8
+/// What the values of a serialized share are elements of
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FieldId {
+    /// `Fp<P>`, with its modulus
+    Fp(u64),
+    /// `Fp255`, the BLS12-381 scalar field
+    Fp255,
+    /// Byte strings shared in 31-byte chunks over `Fp255` (`split_bytes`)
+    Fp255Bytes,
+    /// Byte strings shared byte by byte over GF(2^8) (`split_bytes_gf256`)
+    Gf256Bytes,
+}
 
-// Formula:
-// Given exponent e, e can be written as binary number:
-// e = b0 * 2^0 + b1 * 2^1 + b2 * 2^2 + ... + bn * 2^n
-// Then, a^e can be written as:
-// a^e = a^(b0 * 2^0 + b1 * 2^1 + b2 * 2^2 +... + bn * 2^n)
-// = a^(b0 * 2^0) * a^(b1 * 2^1) * a^(b2 * 2^2) *... * a^(bn * 2^n)
-// = a^(b0) * a^(b0 * 2) * a^(b1 * 2) * a^(b2 * 2^2) *... * a^(bn * 2^n)
-// = a^(b0) * a^(b1) * a^(b2) *... * a^(bn)
-
-// the evaluation looks like:
-// only collect when the last bit of exp is 1 (odd number) then accumulate the result
-// the base is cummulative multiplication of base
-// the exp is right shift by 1 bit (divide by 2)
-// 2^3 = 2^(2^1) * 2^(2^1), 3 = b11
-pub fn mod_pow(base: u64, exp: u64) -> u64 {
-    if exp == 0 {
-        return 1;
+/// Field of integers modulo the prime `P`, which must fit in a `u64`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Fp<const P: u64>(u64);
+
+/// Largest prime below 2^64: 2^64 - 59
+pub type Fp64 = Fp<0xFFFF_FFFF_FFFF_FFC5>;
+
+/// Goldilocks prime 2^64 - 2^32 + 1: p - 1 = 2^32 * (2^32 - 1), so FFTs of up to 2^32 points
+pub type Goldilocks = Fp<0xFFFF_FFFF_0000_0001>;
+
+/// 255-bit scalar field of BLS12-381, large enough for cryptographic keys
+pub type Fp255 = ark_bls12_381::Fr;
+
+impl<const P: u64> Fp<P> {
+    pub const MODULUS: u64 = P;
+
+    /// Barrett constant of P, computed at compile time
+    const MU: u128 = barrett_mu(P);
+
+    pub fn new(value: u64) -> Self {
+        Self(value % P)
     }
-    let mut result = 1u64;
-    let mut base = base;
-    let mut exp = exp;
-    while exp > 0 {
-        // check if exp is odd
-        if exp & 1 == 1 {
-            result = mod_mul(result, base);
+
+    pub fn value(&self) -> u64 {
+        self.0
+    }
+}
+
+impl<const P: u64> Add for Fp<P> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self(mod_add(self.0, rhs.0, P))
+    }
+}
+
+impl<const P: u64> Sub for Fp<P> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Self(mod_sub(self.0, rhs.0, P))
+    }
+}
+
+impl<const P: u64> Mul for Fp<P> {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        Self(barrett_mul(self.0, rhs.0, P, Self::MU))
+    }
+}
+
+impl<const P: u64> Neg for Fp<P> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self(mod_sub(0, self.0, P))
+    }
+}
+
+impl<const P: u64> PrimeField for Fp<P> {
+    const BYTES: usize = 8;
+    const FIELD_ID: FieldId = FieldId::Fp(P);
+
+    fn zero() -> Self {
+        Self(0)
+    }
+
+    fn one() -> Self {
+        Self(1 % P)
+    }
+
+    fn from_u64(value: u64) -> Self {
+        Self::new(value)
+    }
+
+    fn inverse(&self) -> Option<Self> {
+        let inverse = barrett_pow(self.0, P - 2, P, Self::MU);
+        (self.0 != 0).then_some(Self(inverse))
+    }
+
+    fn random<R: RngCore + ?Sized>(rng: &mut R) -> Self {
+        // 2^64 is not a multiple of P: `next_u64() % P` would favour the residues below 2^64 mod P.
+        // Rejecting the lowest 2^64 mod P values leaves a multiple of P, so the result is uniform.
+        let reject_below = P.wrapping_neg() % P;
+        loop {
+            let value = rng.next_u64();
+            if value >= reject_below {
+                return Self(value % P);
+            }
         }
-        base = mod_mul(base, base);
-        exp >>= 1;
     }
-    result
+
+    fn to_bytes(&self) -> Vec<u8> {
+        self.0.to_le_bytes().to_vec()
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        let value = u64::from_le_bytes(bytes.try_into().ok()?);
+        (value < P).then_some(Self(value))
+    }
+}
+
+impl PrimeField for Fp255 {
+    const BYTES: usize = 32;
+    const FIELD_ID: FieldId = FieldId::Fp255;
+
+    fn zero() -> Self {
+        <Self as ark_ff::Zero>::zero()
+    }
+
+    fn one() -> Self {
+        <Self as ark_ff::One>::one()
+    }
+
+    fn from_u64(value: u64) -> Self {
+        Self::from(value)
+    }
+
+    fn inverse(&self) -> Option<Self> {
+        ark_ff::Field::inverse(self)
+    }
+
+    fn random<R: RngCore + ?Sized>(rng: &mut R) -> Self {
+        // reduce 512 random bits so the modulo bias is negligible
+        let mut bytes = [0u8; 64];
+        rng.fill_bytes(&mut bytes);
+        Self::from_le_bytes_mod_order(&bytes)
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        ark_ff::BigInteger::to_bytes_le(&self.into_bigint())
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() != Self::BYTES {
+            return None;
+        }
+        let mut limbs = [0u64; 4];
+        for (limb, chunk) in limbs.iter_mut().zip(bytes.chunks_exact(8)) {
+            *limb = u64::from_le_bytes(chunk.try_into().ok()?);
+        }
+        Self::from_bigint(BigInt::new(limbs))
+    }
+}
+
+impl FftField for Goldilocks {
+    const TWO_ADICITY: u32 = 32;
+
+    fn two_adic_root_of_unity() -> Self {
+        // 7^((p - 1) / 2^32)
+        Self(mod_pow(7, (Self::MODULUS - 1) >> 32, Self::MODULUS))
+    }
+
+    fn multiplicative_generator() -> Self {
+        Self(7)
+    }
+}
+
+impl FftField for Fp255 {
+    const TWO_ADICITY: u32 = <Self as ark_ff::FftField>::TWO_ADICITY;
+
+    fn two_adic_root_of_unity() -> Self {
+        <Self as ark_ff::FftField>::TWO_ADIC_ROOT_OF_UNITY
+    }
+
+    fn multiplicative_generator() -> Self {
+        <Self as ark_ff::FftField>::GENERATOR
+    }
+}
+
+/// Window size of `mod_pow`: the exponent is consumed 4 bits at a time
+const WINDOW_BITS: u32 = 4;
+
+/// ```md
+/// The functions below are constant-time in their secret inputs: no `%` (u128 division runs in
+/// data-dependent time), no branches and no table indices that depend on a, b, base or exp.
+/// Conditional steps compute both outcomes and select one with a mask:
+///
+/// mask = 0 - bit                      all ones if bit = 1, zero if bit = 0
+/// select = (x & mask) | (y & !mask)   x if bit = 1, y otherwise
+///
+/// Inputs must be reduced (a, b < p). The modulus p is public.
+/// ```
+pub fn mod_add(a: u64, b: u64, p: u64) -> u64 {
+    let (sum, carry) = a.overflowing_add(b);
+    let (reduced, borrow) = sum.overflowing_sub(p);
+    // keep the sum only if it is below p: no 64-bit overflow and subtracting p borrows
+    let keep = mask((borrow & !carry) as u64);
+    (sum & keep) | (reduced & !keep)
 }
 
-pub fn mod_inv(a: u64) -> u64 {
+pub fn mod_sub(a: u64, b: u64, p: u64) -> u64 {
+    let (diff, borrow) = a.overflowing_sub(b);
+    diff.wrapping_add(p & mask(borrow as u64))
+}
+
+/// Computes the Barrett constant of p on every call, a u128 division about as slow as the `%` it replaces.
+/// It only depends on the public p, so this stays constant-time in a and b, but the fast path is
+/// `Fp<P>` multiplication, whose constant `MU` is computed at compile time.
+pub fn mod_mul(a: u64, b: u64, p: u64) -> u64 {
+    barrett_mul(a, b, p, barrett_mu(p))
+}
+
+// Eg, 2^300 with a 4-bit window: 300 = 0x12c = [1, 2, 12]
+// 1/ table = [2^0, 2^1, ..., 2^15], result = 1
+// 2/ for each window, from the most significant one:
+//    result = result^16 (4 squarings), then result = result * table[window]
+//    result = 2^1 -> 2^16 * 2^2 = 2^18 -> 2^288 * 2^12 = 2^300
+// Every exponent runs 64 / 4 = 16 windows, leading zero windows included,
+// and every table lookup reads all 16 entries, so neither the time nor the memory accesses depend on exp.
+
+// Formula:
+// Given exponent e, e can be written in base 2^w (w = 4):
+// e = d0 * 2^0 + d1 * 2^w + d2 * 2^(2w) + ... + dn * 2^(nw), 0 <= di < 2^w
+// Then, a^e can be evaluated from the top digit down, Horner style:
+// a^e = (...((a^dn)^(2^w) * a^d(n-1))^(2^w) * ...)^(2^w) * a^d0
+/// Like `mod_mul`, computes the Barrett constant of p on every call, while `Fp<P>::inverse`
+/// exponentiates with the compile-time `MU`.
+pub fn mod_pow(base: u64, exp: u64, p: u64) -> u64 {
+    barrett_pow(base, exp, p, barrett_mu(p))
+}
+
+/// `None` for 0, which has no inverse (Fermat's little theorem would silently give 0^(p-2) = 0).
+/// Only whether `a` is zero leaks through the result, the exponentiation runs either way.
+pub fn mod_inv(a: u64, p: u64) -> Option<u64> {
     // Fermat's little theorem
     // a^p ≡ a (mod p).
     // a^(p-1) ≡ 1 mod p
     // If we multiply both sides by a^(-1), we get:
     // a^(p-2) ≡ a^(-1) mod p
-    mod_pow(a, PRIME - 2)
+    let inverse = mod_pow(a, p - 2, p);
+    (a != 0).then_some(inverse)
+}
+
+/// ```md
+/// Barrett reduction of x < 2^128 modulo p:
+///
+/// mu = floor((2^128 - 1) / p)    precomputed, p is public
+/// q  = floor(x * mu / 2^128)     underestimates floor(x / p) by at most 2
+/// r  = x - q * p < 3p            then at most two masked subtractions of p
+/// ```
+pub const fn barrett_mu(p: u64) -> u128 {
+    u128::MAX / p as u128
+}
+
+fn barrett_mul(a: u64, b: u64, p: u64, mu: u128) -> u64 {
+    let x = a as u128 * b as u128;
+    let q = mul_high(x, mu);
+    let p = p as u128;
+    let mut r = x.wrapping_sub(q.wrapping_mul(p));
+    for _ in 0..2 {
+        let (reduced, borrow) = r.overflowing_sub(p);
+        let keep = black_box(borrow as u128).wrapping_neg();
+        r = (r & keep) | (reduced & !keep);
+    }
+    r as u64
+}
+
+fn barrett_pow(base: u64, exp: u64, p: u64, mu: u128) -> u64 {
+    let mut table = [1u64; 1 << WINDOW_BITS];
+    for i in 1..table.len() {
+        table[i] = barrett_mul(table[i - 1], base, p, mu);
+    }
+
+    let mut result = 1u64;
+    for window in (0..u64::BITS / WINDOW_BITS).rev() {
+        for _ in 0..WINDOW_BITS {
+            result = barrett_mul(result, result, p, mu);
+        }
+        let digit = (exp >> (window * WINDOW_BITS)) & ((1 << WINDOW_BITS) - 1);
+        result = barrett_mul(result, lookup(&table, digit), p, mu);
+    }
+    result
+}
+
+/// High 128 bits of the 256-bit product x * y, schoolbook on 64-bit limbs
+fn mul_high(x: u128, y: u128) -> u128 {
+    let low_mask = u64::MAX as u128;
+    let (x1, x0) = (x >> 64, x & low_mask);
+    let (y1, y0) = (y >> 64, y & low_mask);
+
+    let low = x0 * y0;
+    let cross1 = x1 * y0;
+    let cross2 = x0 * y1;
+    let high = x1 * y1;
+
+    // the middle 64-bit column, its carry goes into the high half
+    let middle = (low >> 64) + (cross1 & low_mask) + (cross2 & low_mask);
+    high + (cross1 >> 64) + (cross2 >> 64) + (middle >> 64)
+}
+
+/// table[index], reading every entry
+fn lookup(table: &[u64], index: u64) -> u64 {
+    table
+        .iter()
+        .zip(0u64..)
+        .fold(0, |acc, (&value, i)| acc | (value & mask(ct_eq(i, index))))
+}
+
+/// 1 if a = b, 0 otherwise, without comparing
+fn ct_eq(a: u64, b: u64) -> u64 {
+    let x = a ^ b;
+    // the top bit of x | -x is set for every x != 0
+    ((x | x.wrapping_neg()) >> 63) ^ 1
+}
+
+/// All ones for bit = 1, zero for bit = 0. `black_box` keeps the compiler
+/// from turning the masked selections back into branches.
+fn mask(bit: u64) -> u64 {
+    black_box(bit).wrapping_neg()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use rand::Rng;
+    use std::time::Instant;
+    const PRIME: u64 = 997;
+
     #[test]
     fn test_mod_pow() {
-        assert_eq!(mod_pow(2, 3), 8);
-        assert_eq!(mod_pow(2, 4), 16);
-        assert_eq!(mod_pow(2, 5), 32);
-        assert_eq!(mod_pow(5, 0), 1);
+        assert_eq!(mod_pow(2, 3, PRIME), 8);
+        assert_eq!(mod_pow(2, 4, PRIME), 16);
+        assert_eq!(mod_pow(2, 5, PRIME), 32);
+        assert_eq!(mod_pow(5, 0, PRIME), 1);
     }
 
     #[test]
     fn test_mod_inv() {
-        assert_eq!(mod_inv(2), mod_pow(2, PRIME - 2));
+        assert_eq!(mod_inv(2, PRIME), Some(mod_pow(2, PRIME - 2, PRIME)));
+        assert_eq!(mod_mul(mod_inv(2, PRIME).unwrap(), 2, PRIME), 1);
+        assert_eq!(mod_inv(0, PRIME), None);
+    }
+
+    #[test]
+    fn test_matches_u128_remainder() {
+        let mut rng = rand::rng();
+        for p in [3, PRIME, (1 << 61) - 1, Fp64::MODULUS] {
+            for _ in 0..1000 {
+                let a = rng.next_u64() % p;
+                let b = rng.next_u64() % p;
+                let (a128, b128, p128) = (a as u128, b as u128, p as u128);
+                assert_eq!(mod_add(a, b, p) as u128, (a128 + b128) % p128);
+                assert_eq!(mod_sub(a, b, p) as u128, (a128 + p128 - b128) % p128);
+                assert_eq!(mod_mul(a, b, p) as u128, a128 * b128 % p128);
+            }
+            assert_eq!(mod_mul(p - 1, p - 1, p), 1);
+            assert_eq!(mod_add(p - 1, p - 1, p), p - 2);
+        }
+    }
+
+    #[test]
+    fn test_mod_pow_fixed_window() {
+        let mut rng = rand::rng();
+        let p = Fp64::MODULUS;
+        for _ in 0..100 {
+            let base = rng.next_u64() % p;
+            let exp = rng.next_u64();
+
+            // plain square-and-multiply
+            let mut expected = 1;
+            for bit in (0..64).rev() {
+                expected = mod_mul(expected, expected, p);
+                if (exp >> bit) & 1 == 1 {
+                    expected = mod_mul(expected, base, p);
+                }
+            }
+            assert_eq!(mod_pow(base, exp, p), expected);
+        }
+        assert_eq!(mod_pow(0, 0, p), 1);
+        assert_eq!(
+            mod_pow(2, 300, PRIME),
+            mod_pow(mod_pow(2, 30, PRIME), 10, PRIME)
+        );
+    }
+
+    #[test]
+    fn test_fp_arithmetic() {
+        type F = Fp<PRIME>;
+        let a = F::from_u64(996);
+        let b = F::from_u64(5);
+        assert_eq!(a + b, F::from_u64(4));
+        assert_eq!(b - a, F::from_u64(6));
+        assert_eq!(-a, F::one());
+        assert_eq!(a * a.inverse().unwrap(), F::one());
+        assert_eq!(F::zero().inverse(), None);
+    }
+
+    /// Replays a fixed sequence of u64 values
+    struct Replay(std::vec::IntoIter<u64>);
+
+    impl RngCore for Replay {
+        fn next_u32(&mut self) -> u32 {
+            self.next_u64() as u32
+        }
+
+        fn next_u64(&mut self) -> u64 {
+            self.0.next().expect("enough values")
+        }
+
+        fn fill_bytes(&mut self, dest: &mut [u8]) {
+            rand::rand_core::impls::fill_bytes_via_next(self, dest)
+        }
+    }
+
+    #[test]
+    fn test_random_rejects_biased_values() {
+        type F = Fp<PRIME>;
+        // 2^64 mod 997 = 961: 0..961 would make the residues below 961 slightly more likely
+        let mut rng = Replay(vec![3, 960, 1000].into_iter());
+        assert_eq!(F::random(&mut rng), F::from_u64(3));
+
+        let mut rng = Replay(vec![961].into_iter());
+        assert_eq!(F::random(&mut rng), F::from_u64(961));
+    }
+
+    #[test]
+    fn test_fp64_large_values() {
+        let a = Fp64::from_u64(u64::MAX);
+        assert_eq!(a, Fp64::from_u64(58));
+        let max = -Fp64::one();
+        assert_eq!(max * max, Fp64::one());
+        assert_eq!(max.inverse(), Some(max));
+    }
+
+    fn assert_primitive_root<F: FftField>() {
+        let mut root = F::two_adic_root_of_unity();
+        for _ in 1..F::TWO_ADICITY {
+            root = root * root;
+            assert_ne!(root, F::one());
+        }
+        // root^(2^s) = 1 and root^(2^(s-1)) = -1
+        assert_eq!(root, -F::one());
+        assert_eq!(root * root, F::one());
+    }
+
+    #[test]
+    fn test_two_adic_roots_of_unity() {
+        assert_primitive_root::<Goldilocks>();
+        assert_primitive_root::<Fp255>();
+        assert_eq!(
+            Goldilocks::two_adic_root_of_unity().value(),
+            0x1856_29dc_da58_878c
+        );
+    }
+
+    #[test]
+    fn test_batch_inverse() {
+        let values = [3, 5, 7, 996].map(Fp::<PRIME>::from_u64);
+        let inverses = batch_inverse(&values).unwrap();
+        for (value, inverse) in values.iter().zip(&inverses) {
+            assert_eq!(*value * *inverse, Fp::one());
+        }
+        assert_eq!(batch_inverse::<Fp<PRIME>>(&[]), Some(vec![]));
+        assert_eq!(batch_inverse(&[Fp::<PRIME>::one(), Fp::zero()]), None);
+    }
+
+    #[test]
+    fn test_bytes_round_trip() {
+        let small = Fp64::from_u64(0xdead_beef);
+        assert_eq!(Fp64::from_bytes(&small.to_bytes()), Some(small));
+        assert_eq!(Fp64::from_bytes(&u64::MAX.to_le_bytes()), None);
+
+        let big = Fp255::from_u64(0xdead_beef) * Fp255::from_u64(u64::MAX);
+        assert_eq!(big.to_bytes().len(), Fp255::BYTES);
+        assert_eq!(Fp255::from_bytes(&big.to_bytes()), Some(big));
+        assert_eq!(Fp255::from_bytes(&[0xff; 32]), None);
+        assert_eq!(Fp255::from_bytes(&[0x01; 31]), None);
+    }
+
+    /// ```md
+    /// dudect-style leakage detection (Reparaz, Balasch, Verbauwhede, "Dude, is my code constant time?"):
+    /// time `op` on a fixed input and on random inputs, interleaved at random, crop the slowest samples
+    /// and compare the two timing distributions with Welch's t-test.
+    /// |t| above 10 means the running time depends on the input.
+    /// ```
+    fn leakage(op: impl Fn(u64) -> u64, fixed: u64, p: u64) -> f64 {
+        const SAMPLES: usize = 200_000;
+        const BATCH: usize = 16;
+
+        let mut rng = rand::rng();
+        let inputs: Vec<(usize, u64)> = (0..SAMPLES)
+            .map(|_| match rng.random::<bool>() {
+                false => (0, fixed),
+                true => (1, rng.next_u64() % p),
+            })
+            .collect();
+
+        let mut times = [Vec::new(), Vec::new()];
+        for &(class, input) in &inputs {
+            let start = Instant::now();
+            for _ in 0..BATCH {
+                black_box(op(black_box(input)));
+            }
+            times[class].push(start.elapsed().as_nanos() as f64);
+        }
+
+        // the slowest samples are interrupts and cache misses, not the operation
+        let mut all: Vec<f64> = times.iter().flatten().copied().collect();
+        all.sort_by(f64::total_cmp);
+        let cutoff = all[all.len() * 9 / 10];
+        let [fixed, random] = times.map(|class| {
+            class
+                .into_iter()
+                .filter(|&time| time <= cutoff)
+                .collect::<Vec<_>>()
+        });
+
+        welch_t(&fixed, &random).abs()
+    }
+
+    fn welch_t(a: &[f64], b: &[f64]) -> f64 {
+        let stats = |x: &[f64]| {
+            let n = x.len() as f64;
+            let mean = x.iter().sum::<f64>() / n;
+            let var = x.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (n - 1.0);
+            (n, mean, var)
+        };
+        let (na, ma, va) = stats(a);
+        let (nb, mb, vb) = stats(b);
+        (ma - mb) / (va / na + vb / nb).sqrt()
+    }
+
+    const LEAKAGE_THRESHOLD: f64 = 10.0;
+
+    // Timing tests are noisy in debug builds and on busy machines, run them with
+    // cargo test --release -- --ignored --test-threads=1
+    #[test]
+    #[ignore]
+    fn test_harness_detects_leakage() {
+        // the square-and-multiply mod_pow used to stop early and skip multiplications by zero bits
+        fn leaky_pow(base: u64, mut exp: u64, p: u64) -> u64 {
+            let mut result = 1;
+            let mut base = base;
+            while exp > 0 {
+                if exp & 1 == 1 {
+                    result = (result as u128 * base as u128 % p as u128) as u64;
+                }
+                base = (base as u128 * base as u128 % p as u128) as u64;
+                exp >>= 1;
+            }
+            result
+        }
+        let p = Fp64::MODULUS;
+        let t = leakage(|exp| leaky_pow(3, exp, p), 1, u64::MAX);
+        assert!(t > LEAKAGE_THRESHOLD, "t = {t}");
+    }
+
+    fn assert_constant_time(name: &str, op: impl Fn(u64) -> u64) {
+        // 1 is the cheapest input for division-based reduction and early-exit loops
+        let t = leakage(op, 1, Fp64::MODULUS);
+        assert!(t < LEAKAGE_THRESHOLD, "{name}: t = {t}");
+    }
+
+    #[test]
+    #[ignore]
+    fn test_constant_time_arithmetic() {
+        let p = Fp64::MODULUS;
+        let b = p - 12345;
+        assert_constant_time("mod_add", |a| mod_add(a, b, p));
+        assert_constant_time("mod_sub", |a| mod_sub(a, b, p));
+        assert_constant_time("mod_mul", |a| mod_mul(a, b, p));
+        assert_constant_time("mod_pow", |exp| mod_pow(b, exp, p));
+        assert_constant_time("mod_inv", |a| mod_inv(a, p).unwrap_or(0));
     }
 }
@@ -0,0 +1,498 @@
+use rand::CryptoRng;
+
+use crate::errors::ShamirError;
+use crate::ff_ops::{FftField, batch_inverse};
+use crate::polynomial::{Polynomial, SubproductTree, TreeArithmetic, generate_polynomial_with_rng};
+use crate::share::{Share, check_field_ids, check_parameters, validate_shares};
+
+/// Below this many coefficients, schoolbook multiplication and long division beat the transforms
+const FFT_THRESHOLD: usize = 64;
+
+/// ```md
+/// Radix-2 evaluation domain: the n-th roots of unity, n a power of two, optionally shifted to a coset
+///
+/// H = {ω^0, ω^1, ..., ω^(n-1)}, ω a primitive n-th root of unity
+/// gH = {g*ω^0, g*ω^1, ..., g*ω^(n-1)} for the coset with offset g
+///
+/// The FFT evaluates a polynomial of degree < n on all of H in O(n log n),
+/// the inverse FFT interpolates it back from its n evaluations.
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct EvaluationDomain<F: FftField> {
+    size: usize,
+    generator: F,
+    generator_inv: F,
+    size_inv: F,
+    offset: F,
+    offset_inv: F,
+}
+
+impl<F: FftField> EvaluationDomain<F> {
+    /// The smallest domain with at least `min_size` points, `None` if the field has no root of unity of that order
+    pub fn new(min_size: usize) -> Option<Self> {
+        let size = min_size.max(1).checked_next_power_of_two()?;
+        let log_size = size.trailing_zeros();
+        if log_size > F::TWO_ADICITY {
+            return None;
+        }
+
+        // square the 2^s-th root of unity down to an n-th root
+        let mut generator = F::two_adic_root_of_unity();
+        for _ in log_size..F::TWO_ADICITY {
+            generator = generator * generator;
+        }
+
+        Some(Self {
+            size,
+            generator,
+            generator_inv: generator.inverse()?,
+            size_inv: F::from_u64(size as u64).inverse()?,
+            offset: F::one(),
+            offset_inv: F::one(),
+        })
+    }
+
+    /// The same domain shifted by `offset`, `None` for a zero offset
+    pub fn coset(&self, offset: F) -> Option<Self> {
+        Some(Self {
+            offset,
+            offset_inv: offset.inverse()?,
+            ..*self
+        })
+    }
+
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// ω, the primitive n-th root of unity
+    pub fn generator(&self) -> F {
+        self.generator
+    }
+
+    pub fn offset(&self) -> F {
+        self.offset
+    }
+
+    /// g*ω^i for i = [0, n)
+    pub fn elements(&self) -> Vec<F> {
+        std::iter::successors(Some(self.offset), |&x| Some(x * self.generator))
+            .take(self.size)
+            .collect()
+    }
+
+    /// x^n - g^n, zero exactly on the domain
+    pub fn vanishing_polynomial(&self) -> Polynomial<F> {
+        let offset_pow = (0..self.size).fold(F::one(), |acc, _| acc * self.offset);
+        let mut coefficients = vec![F::zero(); self.size + 1];
+        coefficients[0] = -offset_pow;
+        coefficients[self.size] = F::one();
+        Polynomial::new(coefficients)
+    }
+
+    /// ```md
+    /// Evaluations f(g*ω^i) of the polynomial with these coefficients, i = [0, n)
+    ///
+    /// On a coset, f(g*x) has coefficients c_i * g^i and is evaluated on H.
+    /// Panics if there are more coefficients than points.
+    /// ```
+    pub fn fft(&self, coefficients: &[F]) -> Vec<F> {
+        assert!(
+            coefficients.len() <= self.size,
+            "{} coefficients do not fit a domain of size {}",
+            coefficients.len(),
+            self.size
+        );
+        let mut values = coefficients.to_vec();
+        distribute_powers(&mut values, self.offset);
+        values.resize(self.size, F::zero());
+        fft_in_place(&mut values, self.generator);
+        values
+    }
+
+    /// ```md
+    /// Coefficients of the polynomial of degree < n with these evaluations on the domain
+    ///
+    /// c = (1/n) * FFT_{ω^-1}(evaluations), then c_i * g^-i on a coset.
+    /// Panics unless there is exactly one evaluation per point.
+    /// ```
+    pub fn ifft(&self, evaluations: &[F]) -> Vec<F> {
+        assert_eq!(
+            evaluations.len(),
+            self.size,
+            "one evaluation per domain point"
+        );
+        let mut values = evaluations.to_vec();
+        fft_in_place(&mut values, self.generator_inv);
+        for value in &mut values {
+            *value = *value * self.size_inv;
+        }
+        distribute_powers(&mut values, self.offset_inv);
+        values
+    }
+}
+
+/// values[i] *= factor^i
+fn distribute_powers<F: FftField>(values: &mut [F], factor: F) {
+    if factor == F::one() {
+        return;
+    }
+    let mut power = F::one();
+    for value in values {
+        *value = *value * power;
+        power = power * factor;
+    }
+}
+
+/// ```md
+/// Iterative Cooley-Tukey: after the bit-reversal permutation, each stage merges pairs of
+/// half-size transforms with the butterfly
+///
+/// (a, b) -> (a + w*b, a - w*b), w = root^(n / len)^j
+///
+/// `root` must be a primitive n-th root of unity, n = values.len() a power of two.
+/// ```
+fn fft_in_place<F: FftField>(values: &mut [F], root: F) {
+    let n = values.len();
+    if n <= 1 {
+        return;
+    }
+    let log_n = n.trailing_zeros();
+
+    for i in 0..n {
+        let j = i.reverse_bits() >> (usize::BITS - log_n);
+        if i < j {
+            values.swap(i, j);
+        }
+    }
+
+    // stage_roots[k] = root^(2^k), the stage with blocks of length len uses root^(n / len)
+    let stage_roots: Vec<F> = std::iter::successors(Some(root), |&w| Some(w * w))
+        .take(log_n as usize)
+        .collect();
+
+    let mut len = 2;
+    while len <= n {
+        let step = stage_roots[(log_n - len.trailing_zeros()) as usize];
+        for block in values.chunks_mut(len) {
+            let (low, high) = block.split_at_mut(len / 2);
+            let mut w = F::one();
+            for (a, b) in low.iter_mut().zip(high) {
+                let t = *b * w;
+                *b = *a - t;
+                *a = *a + t;
+                w = w * step;
+            }
+        }
+        len <<= 1;
+    }
+}
+
+impl<F: FftField> Polynomial<F> {
+    /// Product through the FFT: evaluate both on a domain large enough for the product,
+    /// multiply pointwise and interpolate, O(n log n)
+    pub fn mul_fft(&self, other: &Self) -> Self {
+        let (a, b) = (self.coefficients(), other.coefficients());
+        if a.len().min(b.len()) < FFT_THRESHOLD {
+            return self * other;
+        }
+        let Some(domain) = EvaluationDomain::new(a.len() + b.len() - 1) else {
+            return self * other;
+        };
+
+        let products: Vec<F> = domain
+            .fft(a)
+            .iter()
+            .zip(domain.fft(b))
+            .map(|(&x, y)| x * y)
+            .collect();
+        Self::new(domain.ifft(&products))
+    }
+
+    /// ```md
+    /// `div_rem` through reversed polynomials and a power series inverse:
+    ///
+    /// rev_k(f)(x) = x^(k-1) * f(1/x), the coefficients in reverse order
+    /// rev(q) = rev(a) * rev(b)^(-1) mod x^(deg a - deg b + 1)
+    /// r = a - q * b
+    ///
+    /// O(n log n) with FFT multiplication, `None` when dividing by zero.
+    /// ```
+    pub fn div_rem_fft(&self, divisor: &Self) -> Option<(Self, Self)> {
+        let divisor_degree = divisor.degree()?;
+        let Some(degree) = self.degree().filter(|&degree| degree >= divisor_degree) else {
+            return Some((Self::zero(), self.clone()));
+        };
+        let quotient_len = degree - divisor_degree + 1;
+        if quotient_len.min(divisor_degree + 1) < FFT_THRESHOLD {
+            return self.div_rem(divisor);
+        }
+
+        let inverse = reversed(divisor, divisor_degree + 1).inverse_mod_x(quotient_len)?;
+        let reversed_quotient = reversed(self, degree + 1)
+            .truncated(quotient_len)
+            .mul_fft(&inverse)
+            .truncated(quotient_len);
+        let quotient = reversed(&reversed_quotient, quotient_len);
+        let remainder = self - &quotient.mul_fft(divisor);
+
+        Some((quotient, remainder))
+    }
+
+    /// `evaluate_many` with FFT multiplication and division in the subproduct tree, O(n log^2 n)
+    pub fn evaluate_many_fft(&self, points: &[F]) -> Vec<F> {
+        let tree = SubproductTree::<F, Ntt>::new(points);
+        let mut values = Vec::with_capacity(points.len());
+        tree.evaluate(self, &mut values);
+        values
+    }
+
+    /// ```md
+    /// Fast interpolation through the points (x_i, y_i), `None` if two x_i are equal:
+    ///
+    /// f(x) = Σ(y_i / M'(x_i) * M(x) / (x - x_i)), M(x) = Π(x - x_j)
+    ///
+    /// with the M'(x_i) from multi-point evaluation and the sum combined up the subproduct tree, O(n log^2 n).
+    /// ```
+    pub fn interpolate_fft(points: &[(F, F)]) -> Option<Self> {
+        let xs: Vec<F> = points.iter().map(|&(x, _)| x).collect();
+        let tree = SubproductTree::<F, Ntt>::new(&xs);
+
+        let mut denominators = Vec::with_capacity(xs.len());
+        tree.evaluate(&tree.product().derivative(), &mut denominators);
+        let weights: Vec<F> = batch_inverse(&denominators)?
+            .iter()
+            .zip(points)
+            .map(|(&inverse, &(_, y))| y * inverse)
+            .collect();
+
+        Some(tree.linear_combination(&weights))
+    }
+
+    /// ```md
+    /// g = f^(-1) mod x^k by Newton iteration, doubling the precision each step:
+    ///
+    /// g <- g * (2 - f * g) mod x^(2m)
+    ///
+    /// `None` if the constant term is zero.
+    /// ```
+    fn inverse_mod_x(&self, k: usize) -> Option<Self> {
+        let two = Self::constant(F::one() + F::one());
+        let mut inverse = Self::constant(self.coefficient(0).inverse()?);
+        let mut precision = 1;
+        while precision < k {
+            precision = (2 * precision).min(k);
+            let error = self.truncated(precision).mul_fft(&inverse);
+            inverse = inverse
+                .mul_fft(&(&two - &error.truncated(precision)))
+                .truncated(precision);
+        }
+        Some(inverse)
+    }
+
+    /// f mod x^k
+    fn truncated(&self, k: usize) -> Self {
+        let coefficients = self.coefficients();
+        Self::new(coefficients[..k.min(coefficients.len())].to_vec())
+    }
+}
+
+/// rev_len(f), the first `len` coefficients in reverse order
+fn reversed<F: FftField>(f: &Polynomial<F>, len: usize) -> Polynomial<F> {
+    Polynomial::new((0..len).rev().map(|i| f.coefficient(i)).collect())
+}
+
+/// FFT multiplication and division for the subproduct tree
+struct Ntt;
+
+impl<F: FftField> TreeArithmetic<F> for Ntt {
+    fn mul(a: &Polynomial<F>, b: &Polynomial<F>) -> Polynomial<F> {
+        a.mul_fft(b)
+    }
+
+    fn rem(f: &Polynomial<F>, divisor: &Polynomial<F>) -> Polynomial<F> {
+        let (_, remainder) = f
+            .div_rem_fft(divisor)
+            .expect("a product of linear factors is not zero");
+        remainder
+    }
+}
+
+/// `generate_shares` for large committees: the n shares are evaluated with the subproduct tree
+/// in O(n log^2 n) instead of O(n * t). Same shares as `generate_shares_with_rng` for the same randomness.
+pub fn generate_shares_fft<F: FftField>(
+    secret: F,
+    threshold: u32,
+    total_shares: u32,
+) -> Result<Vec<Share<F>>, ShamirError> {
+    generate_shares_fft_with_rng(secret, threshold, total_shares, &mut rand::rng())
+}
+
+pub fn generate_shares_fft_with_rng<F: FftField>(
+    secret: F,
+    threshold: u32,
+    total_shares: u32,
+    rng: &mut impl CryptoRng,
+) -> Result<Vec<Share<F>>, ShamirError> {
+    check_parameters(threshold, total_shares)?;
+    let ids: Vec<u32> = (1..=total_shares).collect();
+    check_field_ids::<F>(&ids)?;
+
+    let polynomial = Polynomial::new(generate_polynomial_with_rng(secret, threshold, rng)?);
+    let set_id = rng.next_u64();
+
+    let xs: Vec<F> = ids.iter().map(|&id| F::from_u64(id as u64)).collect();
+    let values = polynomial.evaluate_many_fft(&xs);
+
+    Ok(ids
+        .into_iter()
+        .zip(values)
+        .map(|(id, value)| Share {
+            id,
+            value,
+            threshold,
+            set_id,
+        })
+        .collect())
+}
+
+/// ```md
+/// `reconstruct_secret` in O(n log^2 n) instead of O(n^2) multiplications and inversions:
+///
+/// l_i(0) = Π(-x_j / (x_i - x_j)) for j != i = M(0) / (-x_i * M'(x_i))
+///
+/// with M'(x_i) from the subproduct tree and all denominators inverted at once.
+/// ```
+pub fn reconstruct_secret_fft<F: FftField>(shares: &[Share<F>]) -> Result<F, ShamirError> {
+    validate_shares(shares)?;
+    let ids: Vec<u32> = shares.iter().map(|share| share.id).collect();
+    check_field_ids::<F>(&ids)?;
+
+    let xs: Vec<F> = ids.iter().map(|&id| F::from_u64(id as u64)).collect();
+    let tree = SubproductTree::<F, Ntt>::new(&xs);
+    let mut derivatives = Vec::with_capacity(xs.len());
+    tree.evaluate(&tree.product().derivative(), &mut derivatives);
+
+    let denominators: Vec<F> = xs
+        .iter()
+        .zip(&derivatives)
+        .map(|(&x, &derivative)| -x * derivative)
+        .collect();
+    let inverses = batch_inverse(&denominators).expect("ids are distinct and non-zero");
+
+    let sum = shares
+        .iter()
+        .zip(inverses)
+        .fold(F::zero(), |acc, (share, inverse)| {
+            acc + share.value * inverse
+        });
+    Ok(tree.product().coefficient(0) * sum)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ff_ops::{Fp255, Goldilocks, PrimeField};
+    use crate::{generate_shares_with_rng, reconstruct_secret};
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    fn random_polynomial<F: FftField>(len: usize) -> Polynomial<F> {
+        let mut rng = rand::rng();
+        Polynomial::new((0..len).map(|_| F::random(&mut rng)).collect())
+    }
+
+    #[test]
+    fn test_fft_matches_evaluation() {
+        let f = random_polynomial::<Goldilocks>(13);
+        let domain = EvaluationDomain::new(13).unwrap();
+        assert_eq!(domain.size(), 16);
+
+        let expected: Vec<Goldilocks> = domain.elements().iter().map(|&x| f.evaluate(x)).collect();
+        let evaluations = domain.fft(f.coefficients());
+        assert_eq!(evaluations, expected);
+        assert_eq!(Polynomial::new(domain.ifft(&evaluations)), f);
+    }
+
+    #[test]
+    fn test_coset_fft() {
+        let f = random_polynomial::<Fp255>(32);
+        let subgroup = EvaluationDomain::new(32).unwrap();
+        let coset = subgroup.coset(Fp255::multiplicative_generator()).unwrap();
+
+        let evaluations = coset.fft(f.coefficients());
+        for (x, value) in coset.elements().into_iter().zip(&evaluations) {
+            assert_eq!(f.evaluate(x), *value);
+            assert_eq!(coset.vanishing_polynomial().evaluate(x), Fp255::zero());
+            // the coset does not meet the subgroup
+            assert_ne!(subgroup.vanishing_polynomial().evaluate(x), Fp255::zero());
+        }
+        assert_eq!(Polynomial::new(coset.ifft(&evaluations)), f);
+    }
+
+    #[test]
+    fn test_domain_limits() {
+        let domain = EvaluationDomain::<Goldilocks>::new(8).unwrap();
+        for x in domain.elements() {
+            assert_eq!(
+                domain.vanishing_polynomial().evaluate(x),
+                Goldilocks::zero()
+            );
+        }
+        assert_eq!(EvaluationDomain::<Goldilocks>::new(1).unwrap().size(), 1);
+        assert!(EvaluationDomain::<Goldilocks>::new((1 << 32) + 1).is_none());
+        assert!(domain.coset(Goldilocks::zero()).is_none());
+    }
+
+    #[test]
+    fn test_mul_and_div_rem_fft() {
+        let a = random_polynomial::<Goldilocks>(300);
+        let b = random_polynomial::<Goldilocks>(120);
+        assert_eq!(a.mul_fft(&b), &a * &b);
+
+        let (q, r) = a.div_rem_fft(&b).unwrap();
+        assert_eq!(Some((q, r)), a.div_rem(&b));
+        assert_eq!(b.div_rem_fft(&a), Some((Polynomial::zero(), b.clone())));
+        assert_eq!(a.div_rem_fft(&Polynomial::zero()), None);
+    }
+
+    #[test]
+    fn test_evaluate_and_interpolate_fft() {
+        let f = random_polynomial::<Fp255>(200);
+        let xs: Vec<Fp255> = (1..=300).map(Fp255::from_u64).collect();
+
+        let values = f.evaluate_many_fft(&xs);
+        assert_eq!(values, f.evaluate_many(&xs));
+
+        let points: Vec<(Fp255, Fp255)> = xs.into_iter().zip(values).collect();
+        assert_eq!(Polynomial::interpolate_fft(&points[..200]), Some(f.clone()));
+        assert_eq!(Polynomial::interpolate_fft(&points), Some(f));
+
+        let repeated = [points[0], points[1], points[0]];
+        assert_eq!(Polynomial::interpolate_fft(&repeated), None);
+    }
+
+    #[test]
+    fn test_shares_fft_match_naive() {
+        let secret = Goldilocks::from_u64(1234);
+        let shares =
+            generate_shares_fft_with_rng(secret, 150, 400, &mut StdRng::seed_from_u64(3)).unwrap();
+        let naive =
+            generate_shares_with_rng(secret, 150, 400, &mut StdRng::seed_from_u64(3)).unwrap();
+        assert_eq!(shares, naive);
+
+        assert_eq!(reconstruct_secret_fft(&shares[100..250]), Ok(secret));
+        assert_eq!(reconstruct_secret_fft(&shares), reconstruct_secret(&shares));
+        assert_eq!(
+            reconstruct_secret_fft(&shares[..149]),
+            Err(ShamirError::InsufficientShares {
+                got: 149,
+                threshold: 150
+            })
+        );
+
+        let secret = Fp255::from_u64(42);
+        let shares = generate_shares_fft(secret, 3, 5).unwrap();
+        assert_eq!(reconstruct_secret_fft(&shares[2..]), Ok(secret));
+    }
+}
@@ -0,0 +1,189 @@
+// Arithmetic in GF(2^8), the field used by byte-wise secret sharing (HashiCorp Vault, SLIP-39, ...)
+//
+// Elements are polynomials over GF(2) of degree < 8, stored as bytes: 0x57 = x^6 + x^4 + x^2 + x + 1.
+// Addition is XOR, multiplication is polynomial multiplication reduced by the AES polynomial
+// x^8 + x^4 + x^3 + x + 1.
+//
+// 0x03 = x + 1 generates the multiplicative group, so every non-zero a is 3^k for a unique k = log(a):
+// a * b = 3^(log(a) + log(b))
+
+/// x^8 + x^4 + x^3 + x + 1
+pub const AES_POLYNOMIAL: u16 = 0x11b;
+
+const GENERATOR: u8 = 0x03;
+
+/// EXP[k] = 3^k, doubled in length so `EXP[log(a) + log(b)]` never needs a reduction mod 255
+const EXP: [u8; 510] = build_exp_table();
+
+/// LOG[3^k] = k, LOG[0] is unused
+const LOG: [u8; 256] = build_log_table();
+
+const fn build_exp_table() -> [u8; 510] {
+    let mut table = [0u8; 510];
+    let mut value = 1u8;
+    let mut k = 0;
+    while k < 510 {
+        table[k] = value;
+        value = gf_mul_ct(value, GENERATOR);
+        k += 1;
+    }
+    table
+}
+
+const fn build_log_table() -> [u8; 256] {
+    let mut table = [0u8; 256];
+    let mut k = 0;
+    while k < 255 {
+        table[EXP[k] as usize] = k as u8;
+        k += 1;
+    }
+    table
+}
+
+pub fn gf_add(a: u8, b: u8) -> u8 {
+    a ^ b
+}
+
+// in characteristic 2 every element is its own negative
+pub fn gf_sub(a: u8, b: u8) -> u8 {
+    a ^ b
+}
+
+/// Table based multiplication.
+///
+/// Fast, but the table lookups are indexed by secret data: use `gf_mul_ct` on secrets.
+pub fn gf_mul(a: u8, b: u8) -> u8 {
+    if a == 0 || b == 0 {
+        return 0;
+    }
+    EXP[LOG[a as usize] as usize + LOG[b as usize] as usize]
+}
+
+/// Constant-time multiplication (no branches or lookups that depend on the operands)
+///
+/// Shift-and-add: for every bit of b, conditionally add the current multiple of a,
+/// then multiply a by x and reduce it by the AES polynomial when it overflows.
+pub const fn gf_mul_ct(a: u8, b: u8) -> u8 {
+    let mut a = a;
+    let mut b = b;
+    let mut result = 0u8;
+    let mut i = 0;
+    while i < 8 {
+        // 0xff if the lowest bit of b is set, 0x00 otherwise
+        let mask = (b & 1).wrapping_neg();
+        result ^= a & mask;
+
+        let overflow = (a >> 7).wrapping_neg();
+        a = (a << 1) ^ (overflow & (AES_POLYNOMIAL as u8));
+        b >>= 1;
+        i += 1;
+    }
+    result
+}
+
+/// Multiplicative inverse, `None` for zero
+pub fn gf_inv(a: u8) -> Option<u8> {
+    if a == 0 {
+        return None;
+    }
+    Some(EXP[255 - LOG[a as usize] as usize])
+}
+
+/// Constant-time inverse: a^254 = a^(-1) since a^255 = 1, and 0 maps to 0
+pub fn gf_inv_ct(a: u8) -> u8 {
+    // 254 = 0b11111110, square-and-multiply with a fixed sequence of operations
+    let a2 = gf_mul_ct(a, a);
+    let a3 = gf_mul_ct(a2, a);
+    let a6 = gf_mul_ct(a3, a3);
+    let a12 = gf_mul_ct(a6, a6);
+    let a15 = gf_mul_ct(a12, a3);
+    let a30 = gf_mul_ct(a15, a15);
+    let a60 = gf_mul_ct(a30, a30);
+    let a120 = gf_mul_ct(a60, a60);
+    let a126 = gf_mul_ct(a120, a6);
+    let a127 = gf_mul_ct(a126, a);
+    gf_mul_ct(a127, a127)
+}
+
+/// a / b, `None` when dividing by zero
+pub fn gf_div(a: u8, b: u8) -> Option<u8> {
+    gf_inv(b).map(|inverse| gf_mul(a, inverse))
+}
+
+/// ```md
+/// Lagrange interpolation of byte strings at x, byte by byte, with constant-time field operations:
+///
+/// f(x)  = Σ(y_i * l_i(x))
+/// l_i(x) = Π((x - x_j) / (x_i - x_j)) for j != i
+///
+/// The x_i must be distinct and all y_i as long as the first.
+/// ```
+pub fn interpolate(points: &[(u8, &[u8])], x: u8) -> Vec<u8> {
+    let lagrange: Vec<u8> = points
+        .iter()
+        .enumerate()
+        .map(|(i, &(xi, _))| {
+            points
+                .iter()
+                .enumerate()
+                .filter(|&(j, _)| j != i)
+                .fold(1u8, |acc, (_, &(xj, _))| {
+                    gf_mul_ct(acc, gf_mul_ct(gf_sub(x, xj), gf_inv_ct(gf_sub(xi, xj))))
+                })
+        })
+        .collect();
+
+    let len = points.first().map_or(0, |(_, y)| y.len());
+    (0..len)
+        .map(|k| {
+            points
+                .iter()
+                .zip(&lagrange)
+                .fold(0u8, |acc, (&(_, y), &li)| gf_add(acc, gf_mul_ct(y[k], li)))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fips_197_multiplication() {
+        // FIPS-197 section 4.2
+        assert_eq!(gf_mul(0x57, 0x83), 0xc1);
+        assert_eq!(gf_mul(0x57, 0x13), 0xfe);
+        assert_eq!(gf_add(0x57, 0x83), 0xd4);
+    }
+
+    #[test]
+    fn test_small_values() {
+        assert_eq!(gf_mul(3, 7), 9);
+        assert_eq!(gf_mul(3, 0), 0);
+        assert_eq!(gf_mul(0, 3), 0);
+        assert_eq!(gf_div(0, 7), Some(0));
+        assert_eq!(gf_div(3, 3), Some(1));
+        assert_eq!(gf_div(6, 3), Some(2));
+        assert_eq!(gf_div(6, 0), None);
+    }
+
+    #[test]
+    fn test_constant_time_matches_tables() {
+        for a in 0..=255u8 {
+            for b in 0..=255u8 {
+                assert_eq!(gf_mul_ct(a, b), gf_mul(a, b));
+            }
+        }
+    }
+
+    #[test]
+    fn test_inverse() {
+        assert_eq!(gf_inv(0), None);
+        assert_eq!(gf_inv_ct(0), 0);
+        for a in 1..=255u8 {
+            let inverse = gf_inv(a).unwrap();
+            assert_eq!(gf_mul(a, inverse), 1);
+            assert_eq!(gf_inv_ct(a), inverse);
+        }
+    }
+}
@@ -1,21 +1,70 @@
-use std::collections::HashMap;
-
-mod ff_ops;
-use ff_ops::*;
-mod polynomial;
-use polynomial::*;
+pub mod access;
+pub mod arithmetic;
+pub mod beaver;
+pub mod bytes;
+pub mod circuit;
+pub use bytes::{combine_bytes, combine_bytes_gf256, split_bytes, split_bytes_gf256};
+pub mod encoding;
+mod errors;
+pub use errors::*;
+pub mod feldman;
+pub mod ff_ops;
+pub mod fft;
+pub use ff_ops::{FftField, FieldId, Fp, Fp64, Fp255, Goldilocks, PrimeField};
+pub mod gf256;
+pub mod packed;
+pub mod pedersen;
+pub mod policy;
+pub mod polynomial;
+pub use polynomial::*;
+pub mod refresh;
+pub mod reshare;
+pub mod robust;
+pub mod scheme;
+pub use robust::reconstruct_secret_robust;
+pub use scheme::SecretSharing;
+mod share;
+pub mod slip39;
+pub mod spdz;
+use rand::CryptoRng;
+pub use share::Share;
+use share::{check_field_ids, check_parameters, validate_shares};
 
 // Generates shares for all parties
-pub fn generate_shares(secret: u64, threshold: u32, total_shares: u32) -> HashMap<u32, u64> {
-    let polynomial = generate_polynomial(secret, threshold);
-    let mut shares = HashMap::new();
+pub fn generate_shares<F: PrimeField>(
+    secret: F,
+    threshold: u32,
+    total_shares: u32,
+) -> Result<Vec<Share<F>>, ShamirError> {
+    generate_shares_with_rng(secret, threshold, total_shares, &mut rand::rng())
+}
+
+// Generates shares for all parties with randomness from `rng`: the same seed gives the same shares
+pub fn generate_shares_with_rng<F: PrimeField>(
+    secret: F,
+    threshold: u32,
+    total_shares: u32,
+    rng: &mut impl CryptoRng,
+) -> Result<Vec<Share<F>>, ShamirError> {
+    check_parameters(threshold, total_shares)?;
+    let ids: Vec<u32> = (1..=total_shares).collect();
+    check_field_ids::<F>(&ids)?;
 
-    for id in 1..=total_shares {
-        let share = evaluate_polynomial(&polynomial, id as u64);
-        shares.insert(id, share);
+    let polynomial = generate_polynomial_with_rng(secret, threshold, rng)?;
+    let set_id = rng.next_u64();
+    let mut shares = Vec::with_capacity(total_shares as usize);
+
+    for id in ids {
+        let share = evaluate_polynomial(&polynomial, F::from_u64(id as u64));
+        shares.push(Share {
+            id,
+            value: share,
+            threshold,
+            set_id,
+        });
     }
 
-    shares
+    Ok(shares)
 }
 
 // Lagrange interpolation for secret reconstruction
@@ -36,63 +85,191 @@ pub fn generate_shares(secret: u64, threshold: u32, total_shares: u32) -> HashMa
 ///
 /// if dont know how the code works, write the formula in paper form and solve for x=0
 ///
-///
-pub fn reconstruct_secret(shares: &HashMap<u32, u64>) -> u64 {
-    let mut secret = 0u64;
-
-    for (&i, &share_i) in shares {
-        let mut lagrange = 1u64;
-
-        for (&j, _) in shares {
-            if i != j {
-                // Calculate lagrange basis polynomial
-                // li(x) = Π((x - xj) / (xi - xj)) for j!= i
-                // li(0) = Π((0 - xj) / (xi - xj)) for j!= i
-                // li(0) = Π((-xj) / (xi - xj)) for j!= i
-
-                // We can calculate the formula above in two ways:
-                // 1
-                let num = PRIME - j as u64;
-                let den = mod_sub(i as u64, j as u64);
-                let inverse = mod_inv(den);
-                lagrange = mod_mul(lagrange, mod_mul(num, inverse));
-
-                // 2
-                // let num = j as u64;
-                // let den = mod_sub(j as u64, i as u64);
-                // let inverse = mod_inv(den);
-                // lagrange = mod_mul(lagrange, mod_mul(num, inverse));
-            }
-        }
+/// The shares must come from the same `generate_shares` call, have distinct ids and reach the threshold.
+pub fn reconstruct_secret<F: PrimeField>(shares: &[Share<F>]) -> Result<F, ShamirError> {
+    validate_shares(shares)?;
+    let ids: Vec<u32> = shares.iter().map(|share| share.id).collect();
+    check_field_ids::<F>(&ids)?;
 
-        secret = mod_add(secret, mod_mul(share_i, lagrange));
+    Ok(interpolate_at_zero(
+        &ids,
+        shares.iter().map(|share| share.value),
+    ))
+}
+
+/// f(0) = Σ(yi * li(0)), `values` in the order of `ids`, which must be distinct non-zero field elements
+pub(crate) fn interpolate_at_zero<F: PrimeField>(
+    ids: &[u32],
+    values: impl IntoIterator<Item = F>,
+) -> F {
+    let mut secret = F::zero();
+
+    for (&i, share_i) in ids.iter().zip(values) {
+        secret = secret + share_i * lagrange_coefficient(i, ids);
     }
 
     secret
 }
 
+/// li(0), the weight of party i's share when interpolating f(0) from the shares of `ids`
+///
+/// Panics if two ids are equal modulo the field prime.
+pub fn lagrange_coefficient<F: PrimeField>(i: u32, ids: &[u32]) -> F {
+    let mut lagrange = F::one();
+    let xi = F::from_u64(i as u64);
+
+    for &j in ids {
+        if i != j {
+            // Calculate lagrange basis polynomial
+            // li(x) = Π((x - xj) / (xi - xj)) for j!= i
+            // li(0) = Π((0 - xj) / (xi - xj)) for j!= i
+            // li(0) = Π((-xj) / (xi - xj)) for j!= i
+
+            // We can calculate the formula above in two ways:
+            let xj = F::from_u64(j as u64);
+
+            // 1
+            let num = -xj;
+            let den = xi - xj;
+            let inverse = den.inverse().expect("share ids must be distinct");
+            lagrange = lagrange * num * inverse;
+
+            // 2
+            // let num = xj;
+            // let den = xj - xi;
+            // let inverse = den.inverse().expect("share ids must be distinct");
+            // lagrange = lagrange * num * inverse;
+        }
+    }
+
+    lagrange
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    const PRIME: u64 = 997;
+
     #[test]
     fn test_share_generation_and_reconstruction() {
         let threshold = 3;
         let total_shares = 5;
 
         for secret in 1..PRIME - 1 {
-            let shares = generate_shares(secret, threshold, total_shares);
+            let secret = Fp::<PRIME>::from_u64(secret);
+            let shares = generate_shares(secret, threshold, total_shares).unwrap();
             println!("Shares: {:?}", shares);
             assert_eq!(shares.len(), total_shares as usize);
 
             // Test reconstruction with exactly threshold shares
-            let mut threshold_shares: HashMap<u32, u64> = HashMap::new();
-            for i in 1..=threshold {
-                threshold_shares.insert(i, *shares.get(&i).unwrap());
-            }
+            let threshold_shares = &shares[..threshold as usize];
 
-            let reconstructed = reconstruct_secret(&threshold_shares);
+            let reconstructed = reconstruct_secret(threshold_shares).unwrap();
             assert_eq!(reconstructed, secret);
         }
     }
+
+    #[test]
+    fn test_32_byte_secret_in_big_field() {
+        let mut bytes = [0u8; 32];
+        for (i, byte) in bytes.iter_mut().enumerate() {
+            *byte = (i as u8).wrapping_mul(37);
+        }
+        bytes[31] &= 0x3f; // keep the secret below the 255-bit modulus
+        let secret = Fp255::from_bytes(&bytes).unwrap();
+
+        let shares = generate_shares(secret, 3, 5).unwrap();
+        let subset: Vec<Share<Fp255>> = shares
+            .into_iter()
+            .filter(|share| [2, 4, 5].contains(&share.id))
+            .collect();
+
+        let reconstructed = reconstruct_secret(&subset).unwrap();
+        assert_eq!(reconstructed.to_bytes(), bytes);
+    }
+
+    #[test]
+    fn test_seeded_rng_is_reproducible() {
+        use rand::SeedableRng;
+        use rand::rngs::StdRng;
+
+        let secret = Fp255::from_u64(42);
+        let shares = generate_shares_with_rng(secret, 3, 5, &mut StdRng::seed_from_u64(7)).unwrap();
+        let again = generate_shares_with_rng(secret, 3, 5, &mut StdRng::seed_from_u64(7)).unwrap();
+        let other = generate_shares_with_rng(secret, 3, 5, &mut StdRng::seed_from_u64(8)).unwrap();
+
+        assert_eq!(shares, again);
+        assert_ne!(shares[0].value, other[0].value);
+        assert_ne!(shares[0].set_id, other[0].set_id);
+        assert_eq!(reconstruct_secret(&shares[2..]), Ok(secret));
+    }
+
+    #[test]
+    fn test_below_threshold_is_rejected() {
+        let secret = Fp64::from_u64(0x1234_5678_9abc_def0);
+        let shares = generate_shares(secret, 3, 5).unwrap();
+        assert_eq!(
+            reconstruct_secret(&shares[..2]),
+            Err(ShamirError::InsufficientShares {
+                got: 2,
+                threshold: 3
+            })
+        );
+
+        // interpolating them anyway does not reveal the secret
+        let ids = [shares[0].id, shares[1].id];
+        let values = [shares[0].value, shares[1].value];
+        assert_ne!(interpolate_at_zero(&ids, values), secret);
+    }
+
+    #[test]
+    fn test_invalid_parameters_are_rejected() {
+        let secret = Fp64::from_u64(42);
+        assert_eq!(
+            generate_shares(secret, 0, 5),
+            Err(ShamirError::InvalidThreshold {
+                threshold: 0,
+                total_shares: 5
+            })
+        );
+        assert_eq!(
+            generate_shares(secret, 6, 5),
+            Err(ShamirError::InvalidThreshold {
+                threshold: 6,
+                total_shares: 5
+            })
+        );
+
+        // id 7 would be the point x = 0, which is the secret itself
+        assert_eq!(
+            generate_shares(Fp::<7>::from_u64(3), 2, 7),
+            Err(ShamirError::InvalidShareId(7))
+        );
+    }
+
+    #[test]
+    fn test_misused_shares_are_rejected() {
+        let shares = generate_shares(Fp64::from_u64(42), 2, 3).unwrap();
+        let other = generate_shares(Fp64::from_u64(42), 2, 3).unwrap();
+
+        let duplicated = [shares[0].clone(), shares[0].clone()];
+        assert_eq!(
+            reconstruct_secret(&duplicated),
+            Err(ShamirError::DuplicateShareId(1))
+        );
+
+        let mixed = [shares[0].clone(), other[1].clone()];
+        assert_eq!(
+            reconstruct_secret(&mixed),
+            Err(ShamirError::MismatchedShares)
+        );
+
+        let mut zero = shares[1].clone();
+        zero.id = 0;
+        assert_eq!(
+            reconstruct_secret(&[shares[0].clone(), zero]),
+            Err(ShamirError::InvalidShareId(0))
+        );
+    }
 }
@@ -0,0 +1,334 @@
+use rand::CryptoRng;
+
+use crate::errors::ShamirError;
+use crate::ff_ops::PrimeField;
+use crate::polynomial::Polynomial;
+use crate::share::{Share, check_field_ids, check_parameters, validate_shares};
+
+/// A share of a packed sharing, with the number of secrets k packed into it
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PackedShare<F> {
+    /// `threshold` is t + k
+    pub share: Share<F>,
+    pub count: u32,
+}
+
+/// ```md
+/// Franklin-Yung packed secret sharing: k secrets in one polynomial
+///
+/// The secrets s_0..s_{k-1} sit at the points e_j = -j, the shares at the ids 1..n as usual:
+///
+/// P(x) = L(x) + Z(x) * r(x)
+///
+/// L(x) = the polynomial of degree < k with L(e_j) = s_j
+/// Z(x) = Π(x - e_j)
+/// r(x) = random polynomial of degree t - 1
+///
+/// P has degree t + k - 1, so t + k shares reconstruct all k secrets, and t shares reveal
+/// nothing: together with any choice of secrets they are t + k points, which fix exactly one P.
+/// Each share is one field element, k times less than sharing the secrets one by one.
+///
+/// With k = 1 this is `generate_shares` with threshold t + 1: L = s_0, Z = x and
+/// P = s_0 + x * r(x).
+/// ```
+pub fn generate_packed_shares<F: PrimeField>(
+    secrets: &[F],
+    threshold: u32,
+    total_shares: u32,
+) -> Result<Vec<PackedShare<F>>, ShamirError> {
+    generate_packed_shares_with_rng(secrets, threshold, total_shares, &mut rand::rng())
+}
+
+/// `generate_packed_shares` with randomness from `rng`: the same seed gives the same shares
+pub fn generate_packed_shares_with_rng<F: PrimeField>(
+    secrets: &[F],
+    threshold: u32,
+    total_shares: u32,
+    rng: &mut impl CryptoRng,
+) -> Result<Vec<PackedShare<F>>, ShamirError> {
+    if secrets.is_empty() {
+        return Err(ShamirError::InvalidSecretLength(0));
+    }
+    if threshold == 0 {
+        return Err(ShamirError::InvalidThreshold {
+            threshold,
+            total_shares,
+        });
+    }
+    let packed_threshold = threshold
+        .checked_add(secrets.len() as u32)
+        .ok_or(ShamirError::InvalidSecretLength(secrets.len()))?;
+    check_parameters(packed_threshold, total_shares)?;
+    let ids: Vec<u32> = (1..=total_shares).collect();
+    check_packed_ids::<F>(&ids, secrets.len())?;
+
+    let random: Vec<F> = (0..threshold).map(|_| F::random(rng)).collect();
+    let polynomial = packed_polynomial(secrets, random);
+    let set_id = rng.next_u64();
+
+    let xs: Vec<F> = ids.iter().map(|&id| F::from_u64(id as u64)).collect();
+    Ok(ids
+        .into_iter()
+        .zip(polynomial.evaluate_many(&xs))
+        .map(|(id, value)| PackedShare {
+            share: Share {
+                id,
+                value,
+                threshold: packed_threshold,
+                set_id,
+            },
+            count: secrets.len() as u32,
+        })
+        .collect())
+}
+
+/// Recovers the k secrets packed into the shares by `generate_packed_shares`
+///
+/// Needs t + k shares, the `threshold` stored in them. k is the `count` stored in them:
+/// any other slot of P holds a random value, not a secret.
+pub fn reconstruct_packed_secrets<F: PrimeField>(
+    packed: &[PackedShare<F>],
+) -> Result<Vec<F>, ShamirError> {
+    let shares: Vec<Share<F>> = packed.iter().map(|share| share.share.clone()).collect();
+    let threshold = validate_shares(&shares)?;
+    let count = packed[0].count as usize;
+    if packed.iter().any(|share| share.count != packed[0].count) {
+        return Err(ShamirError::MismatchedShares);
+    }
+    if count == 0 || count >= threshold as usize {
+        return Err(ShamirError::InvalidSecretLength(count));
+    }
+    let ids: Vec<u32> = shares.iter().map(|share| share.id).collect();
+    check_packed_ids::<F>(&ids, count)?;
+
+    // t + k shares determine P, more would only raise the cost of interpolation
+    let points: Vec<(F, F)> = shares[..threshold as usize]
+        .iter()
+        .map(|share| (F::from_u64(share.id as u64), share.value))
+        .collect();
+    let polynomial = Polynomial::interpolate(&points).expect("ids are distinct");
+
+    Ok(polynomial.evaluate_many(&secret_points::<F>(count)))
+}
+
+/// L(x) + Z(x) * r(x), with the coefficients of r in `random`
+fn packed_polynomial<F: PrimeField>(secrets: &[F], random: Vec<F>) -> Polynomial<F> {
+    let points = secret_points::<F>(secrets.len());
+    let lagrange = Polynomial::interpolate(
+        &points
+            .iter()
+            .copied()
+            .zip(secrets.iter().copied())
+            .collect::<Vec<_>>(),
+    )
+    .expect("secret points are distinct");
+    &lagrange + &(&Polynomial::from_roots(&points) * &Polynomial::new(random))
+}
+
+/// e_j = -j for j = 0..count
+fn secret_points<F: PrimeField>(count: usize) -> Vec<F> {
+    (0..count as u64).map(|j| -F::from_u64(j)).collect()
+}
+
+/// The ids must also miss the secret points: id = -j would hand out secret j
+fn check_packed_ids<F: PrimeField>(ids: &[u32], count: usize) -> Result<(), ShamirError> {
+    check_field_ids::<F>(ids)?;
+    let points = secret_points::<F>(count);
+    match ids
+        .iter()
+        .find(|&&id| points.contains(&F::from_u64(id as u64)))
+    {
+        Some(&id) => Err(ShamirError::InvalidShareId(id)),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    use super::*;
+    use crate::{Fp, Fp255, generate_shares_with_rng, reconstruct_secret};
+
+    const PRIME: u64 = 997;
+
+    type F = Fp<PRIME>;
+
+    fn secrets(count: u64) -> Vec<F> {
+        (0..count).map(|i| F::from_u64(i * 31 + 5)).collect()
+    }
+
+    #[test]
+    fn test_packed_sharing_round_trip() {
+        let secrets = secrets(4);
+        let shares = generate_packed_shares(&secrets, 3, 10).unwrap();
+        assert_eq!(shares.len(), 10);
+        assert!(
+            shares
+                .iter()
+                .all(|share| share.share.threshold == 7 && share.count == 4)
+        );
+
+        assert_eq!(
+            reconstruct_packed_secrets(&shares[3..]),
+            Ok(secrets.clone())
+        );
+        assert_eq!(reconstruct_packed_secrets(&shares), Ok(secrets.clone()));
+        assert_eq!(
+            reconstruct_packed_secrets(&shares[..6]),
+            Err(ShamirError::InsufficientShares {
+                got: 6,
+                threshold: 7
+            })
+        );
+    }
+
+    #[test]
+    fn test_one_secret_is_plain_shamir() {
+        let secret = F::from_u64(123);
+        let packed =
+            generate_packed_shares_with_rng(&[secret], 2, 5, &mut StdRng::seed_from_u64(9))
+                .unwrap();
+        let plain = generate_shares_with_rng(secret, 3, 5, &mut StdRng::seed_from_u64(9)).unwrap();
+
+        let packed: Vec<Share<F>> = packed.into_iter().map(|share| share.share).collect();
+        assert_eq!(packed, plain);
+        assert_eq!(reconstruct_secret(&packed[2..]), Ok(secret));
+    }
+
+    #[test]
+    fn test_packed_compared_to_individual_sharing() {
+        // n = 12 parties tolerating t = 3: 8 secrets in one packed sharing, or in 8 sharings
+        // with threshold t + 1
+        let secrets: Vec<Fp255> = (0..8).map(|i| Fp255::from_u64(1000 + i)).collect();
+        let packed = generate_packed_shares(&secrets, 3, 12).unwrap();
+        let individual: Vec<Vec<Share<Fp255>>> = secrets
+            .iter()
+            .map(|&secret| crate::generate_shares(secret, 4, 12).unwrap())
+            .collect();
+
+        // every party holds 1 field element instead of 8
+        assert_eq!(packed.len(), 12);
+        assert!(individual.iter().all(|shares| shares.len() == 12));
+
+        // the price: 11 shares to recover the secrets instead of 4
+        assert!(packed.iter().all(|share| share.share.threshold == 11));
+        assert!(
+            individual
+                .iter()
+                .flatten()
+                .all(|share| share.threshold == 4)
+        );
+        assert_eq!(
+            reconstruct_packed_secrets(&packed[1..]),
+            Ok(secrets.clone())
+        );
+        assert_eq!(
+            reconstruct_packed_secrets(&packed[..10]),
+            Err(ShamirError::InsufficientShares {
+                got: 10,
+                threshold: 11
+            })
+        );
+        let recovered: Vec<Fp255> = individual
+            .iter()
+            .map(|shares| reconstruct_secret(&shares[8..]).unwrap())
+            .collect();
+        assert_eq!(recovered, secrets);
+
+        // both are linear: adding shares adds the secrets slot by slot
+        let other: Vec<Fp255> = (0..8).map(|i| Fp255::from_u64(7 * i)).collect();
+        let other_packed = generate_packed_shares(&other, 3, 12).unwrap();
+        let sums: Vec<PackedShare<Fp255>> = packed
+            .iter()
+            .zip(&other_packed)
+            .map(|(a, b)| PackedShare {
+                share: Share {
+                    value: a.share.value + b.share.value,
+                    ..a.share.clone()
+                },
+                count: a.count,
+            })
+            .collect();
+        let expected: Vec<Fp255> = secrets.iter().zip(&other).map(|(&a, &b)| a + b).collect();
+        assert_eq!(reconstruct_packed_secrets(&sums), Ok(expected));
+    }
+
+    #[test]
+    fn test_threshold_shares_reveal_nothing() {
+        // in GF(11) with t = 2, k = 2: over all 121 choices of r, the shares 1 and 2 take every
+        // pair of values exactly once, whatever the secrets
+        type F = Fp<11>;
+        let distribution = |secrets: [u64; 2]| {
+            let secrets = secrets.map(F::from_u64);
+            let mut pairs: Vec<(F, F)> = (0..121)
+                .map(|r| {
+                    let random = vec![F::from_u64(r % 11), F::from_u64(r / 11)];
+                    let polynomial = packed_polynomial(&secrets, random);
+                    (
+                        polynomial.evaluate(F::from_u64(1)),
+                        polynomial.evaluate(F::from_u64(2)),
+                    )
+                })
+                .collect();
+            pairs.sort_by_key(|&(a, b)| (a.to_bytes(), b.to_bytes()));
+            pairs.dedup();
+            pairs
+        };
+
+        let uniform = distribution([0, 0]);
+        assert_eq!(uniform.len(), 121);
+        for secrets in [[1, 2], [10, 10], [5, 0]] {
+            assert_eq!(distribution(secrets), uniform);
+        }
+    }
+
+    #[test]
+    fn test_invalid_packed_parameters_are_rejected() {
+        assert_eq!(
+            generate_packed_shares::<F>(&[], 2, 5),
+            Err(ShamirError::InvalidSecretLength(0))
+        );
+        assert_eq!(
+            generate_packed_shares(&secrets(3), 0, 5),
+            Err(ShamirError::InvalidThreshold {
+                threshold: 0,
+                total_shares: 5
+            })
+        );
+        assert_eq!(
+            generate_packed_shares(&secrets(3), 3, 5),
+            Err(ShamirError::InvalidThreshold {
+                threshold: 6,
+                total_shares: 5
+            })
+        );
+        // in GF(11) id 9 is the point -2 of the third secret
+        assert_eq!(
+            generate_packed_shares(&[Fp::<11>::from_u64(1); 3], 2, 9),
+            Err(ShamirError::InvalidShareId(9))
+        );
+
+        let shares = generate_packed_shares(&secrets(2), 2, 5).unwrap();
+        for count in [0, 4] {
+            let tampered: Vec<PackedShare<F>> = shares
+                .iter()
+                .map(|share| PackedShare {
+                    count,
+                    ..share.clone()
+                })
+                .collect();
+            assert_eq!(
+                reconstruct_packed_secrets(&tampered),
+                Err(ShamirError::InvalidSecretLength(count as usize))
+            );
+        }
+        let mut mixed = shares.clone();
+        mixed[1].count = 3;
+        assert_eq!(
+            reconstruct_packed_secrets(&mixed),
+            Err(ShamirError::MismatchedShares)
+        );
+    }
+}
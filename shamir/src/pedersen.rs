@@ -0,0 +1,297 @@
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+use ark_bls12_381::{G1Affine, G1Projective, g1::Config};
+use ark_ec::hashing::{
+    HashToCurve, curve_maps::wb::WBMap, map_to_curve_hasher::MapToCurveBasedHasher,
+};
+use ark_ec::{CurveGroup, PrimeGroup};
+use ark_ff::field_hashers::DefaultFieldHasher;
+use sha2::Sha256;
+
+use crate::errors::ShamirError;
+use crate::ff_ops::{Fp255, PrimeField};
+use crate::polynomial::{evaluate_polynomial, generate_polynomial};
+use crate::share::{Share, check_parameters, new_set_id};
+
+/// Domain separation tag used to derive the second generator h
+const H_DST: &[u8] = b"SHAMIR-PEDERSEN-VSS-V01-CS01-with-BLS12381G1_XMD:SHA-256_SSWU_RO_";
+
+/// ```md
+/// Pedersen verifiable secret sharing
+///
+/// Feldman's C_0 = g^s leaks g^s. Pedersen blinds every commitment with a second generator h,
+/// whose discrete log base g nobody knows (it is hashed to the curve):
+///
+/// f(x) = s + a_1*x + ... + a_{t-1}*x^{t-1}   the sharing polynomial
+/// r(x) = b_0 + b_1*x + ... + b_{t-1}*x^{t-1} a random blinding polynomial
+///
+/// C_k = g^{a_k} * h^{b_k}, k = [0, t)
+///
+/// Party i receives (s_i, t_i) = (f(i), r(i)) and checks
+///
+/// g^{s_i} * h^{t_i} = Π(C_k^{i^k})
+///
+/// C_0 = g^s * h^{b_0} is uniformly random whatever s is, so the commitments hide the secret
+/// information-theoretically, while binding the dealer as long as log_g(h) stays unknown.
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PedersenShare {
+    /// s_i = f(i), the share of the secret
+    pub share: Share<Fp255>,
+    /// t_i = r(i), the share of the blinding polynomial
+    pub blinding: Fp255,
+}
+
+/// Outcome of the complaint round
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ComplaintResolution {
+    /// Every complaint was answered with a share that verifies: the dealer stays,
+    /// and the complaining parties use the published shares
+    Resolved(Vec<PedersenShare>),
+    /// The dealer did not answer these complaints or answered with shares that fail verification
+    DealerDisqualified(Vec<u32>),
+}
+
+/// The second generator h, with unknown discrete log base g, hashed to the curve once
+pub fn second_generator() -> G1Affine {
+    static H: OnceLock<G1Affine> = OnceLock::new();
+    *H.get_or_init(|| {
+        let hasher = MapToCurveBasedHasher::<
+            G1Projective,
+            DefaultFieldHasher<Sha256, 128>,
+            WBMap<Config>,
+        >::new(H_DST)
+        .expect("BLS12-381 G1 supports hashing to the curve");
+        hasher
+            .hash(b"h")
+            .expect("BLS12-381 G1 supports hashing to the curve")
+    })
+}
+
+/// C_k = g^{a_k} * h^{b_k} for the sharing polynomial a and the blinding polynomial b,
+/// which must have the same number of coefficients
+pub fn commit_polynomials(a: &[Fp255], b: &[Fp255]) -> Result<Vec<G1Affine>, ShamirError> {
+    if a.len() != b.len() {
+        return Err(ShamirError::PolynomialLengthMismatch {
+            sharing: a.len(),
+            blinding: b.len(),
+        });
+    }
+
+    let g = G1Projective::generator();
+    let h = G1Projective::from(second_generator());
+    let commitments: Vec<G1Projective> = a
+        .iter()
+        .zip(b)
+        .map(|(&a_k, &b_k)| g * a_k + h * b_k)
+        .collect();
+    Ok(G1Projective::normalize_batch(&commitments))
+}
+
+/// Generates the shares (s_i, t_i) for all parties and the commitments to both polynomials
+pub fn generate_shares(
+    secret: Fp255,
+    threshold: u32,
+    total_shares: u32,
+) -> Result<(Vec<PedersenShare>, Vec<G1Affine>), ShamirError> {
+    check_parameters(threshold, total_shares)?;
+    let sharing = generate_polynomial(secret, threshold)?;
+    let blinding = generate_polynomial(Fp255::random(&mut rand::rng()), threshold)?;
+    let commitments = commit_polynomials(&sharing, &blinding)?;
+
+    let set_id = new_set_id();
+    let shares = (1..=total_shares)
+        .map(|id| {
+            let x = Fp255::from_u64(id as u64);
+            PedersenShare {
+                share: Share {
+                    id,
+                    value: evaluate_polynomial(&sharing, x),
+                    threshold,
+                    set_id,
+                },
+                blinding: evaluate_polynomial(&blinding, x),
+            }
+        })
+        .collect();
+
+    Ok((shares, commitments))
+}
+
+/// Checks g^{s_i} * h^{t_i} = Π(C_k^{i^k}), and that there are `threshold` commitments
+/// for the threshold the parties agreed on (see `feldman::verify_share`)
+pub fn verify_share(share: &PedersenShare, threshold: u32, commitments: &[G1Affine]) -> bool {
+    if commitments.len() != threshold as usize {
+        return false;
+    }
+    let lhs = G1Projective::generator() * share.share.value + second_generator() * share.blinding;
+    lhs == crate::feldman::evaluate_commitments(share.share.id, commitments)
+}
+
+/// Reconstructs the secret from the s_i of at least `threshold` shares of one sharing
+pub fn reconstruct_secret(shares: &[PedersenShare]) -> Result<Fp255, ShamirError> {
+    let shares: Vec<Share<Fp255>> = shares.iter().map(|share| share.share.clone()).collect();
+    crate::reconstruct_secret(&shares)
+}
+
+/// Dealer side of the complaint round: publish the shares of every complaining party
+pub fn answer_complaints(shares: &[PedersenShare], complaints: &[u32]) -> Vec<PedersenShare> {
+    shares
+        .iter()
+        .filter(|share| complaints.contains(&share.share.id))
+        .cloned()
+        .collect()
+}
+
+/// Every party's side of the complaint round.
+///
+/// Parties whose share failed `verify_share` broadcast their id as a complaint,
+/// the dealer answers by publishing those shares, and everybody checks the answers against the commitments.
+pub fn resolve_complaints(
+    complaints: &[u32],
+    answers: &[PedersenShare],
+    threshold: u32,
+    commitments: &[G1Affine],
+) -> ComplaintResolution {
+    let answers: HashMap<u32, &PedersenShare> = answers
+        .iter()
+        .map(|share| (share.share.id, share))
+        .collect();
+
+    let mut revealed = Vec::with_capacity(complaints.len());
+    let mut faults = Vec::new();
+    for &id in complaints {
+        match answers.get(&id) {
+            Some(&share) if verify_share(share, threshold, commitments) => {
+                revealed.push(share.clone())
+            }
+            _ => faults.push(id),
+        }
+    }
+
+    if faults.is_empty() {
+        ComplaintResolution::Resolved(revealed)
+    } else {
+        ComplaintResolution::DealerDisqualified(faults)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_honest_dealer() {
+        let secret = Fp255::from_u64(42);
+        let (shares, commitments) = generate_shares(secret, 3, 5).unwrap();
+        assert_eq!(commitments.len(), 3);
+        assert!(
+            shares
+                .iter()
+                .all(|share| verify_share(share, 3, &commitments))
+        );
+        assert_eq!(reconstruct_secret(&shares[1..4]), Ok(secret));
+        assert_eq!(
+            reconstruct_secret(&shares[..2]),
+            Err(ShamirError::InsufficientShares {
+                got: 2,
+                threshold: 3
+            })
+        );
+    }
+
+    #[test]
+    fn test_higher_degree_dealer_is_detected() {
+        let sharing = generate_polynomial(Fp255::from_u64(42), 4).unwrap();
+        let blinding = generate_polynomial(Fp255::from_u64(7), 4).unwrap();
+        let commitments = commit_polynomials(&sharing, &blinding).unwrap();
+        let share = PedersenShare {
+            share: Share {
+                id: 1,
+                value: evaluate_polynomial(&sharing, Fp255::one()),
+                threshold: 3,
+                set_id: 0,
+            },
+            blinding: evaluate_polynomial(&blinding, Fp255::one()),
+        };
+        assert!(verify_share(&share, 4, &commitments));
+        assert!(!verify_share(&share, 3, &commitments));
+    }
+
+    #[test]
+    fn test_polynomials_of_different_lengths() {
+        let sharing = generate_polynomial(Fp255::from_u64(42), 3).unwrap();
+        let blinding = generate_polynomial(Fp255::from_u64(7), 2).unwrap();
+        assert_eq!(
+            commit_polynomials(&sharing, &blinding),
+            Err(ShamirError::PolynomialLengthMismatch {
+                sharing: 3,
+                blinding: 2
+            })
+        );
+    }
+
+    #[test]
+    fn test_commitments_hide_the_secret() {
+        let secret = Fp255::from_u64(42);
+        let (_, commitments) = generate_shares(secret, 3, 5).unwrap();
+        let (_, other_commitments) = generate_shares(secret, 3, 5).unwrap();
+
+        assert_ne!(
+            commitments[0],
+            (G1Projective::generator() * secret).into_affine()
+        );
+        assert_ne!(commitments[0], other_commitments[0]);
+    }
+
+    #[test]
+    fn test_tampered_share_fails_verification() {
+        let (shares, commitments) = generate_shares(Fp255::from_u64(42), 3, 5).unwrap();
+
+        let mut bad = shares[1].clone();
+        bad.share.value += Fp255::one();
+        assert!(!verify_share(&bad, 3, &commitments));
+
+        let mut bad = shares[1].clone();
+        bad.blinding += Fp255::one();
+        assert!(!verify_share(&bad, 3, &commitments));
+    }
+
+    #[test]
+    fn test_complaint_answered_correctly() {
+        let (shares, commitments) = generate_shares(Fp255::from_u64(42), 3, 5).unwrap();
+
+        // party 2 received garbage in private and complains
+        let mut received = shares.clone();
+        received[1].share.value += Fp255::one();
+        let complaints: Vec<u32> = received
+            .iter()
+            .filter(|share| !verify_share(share, 3, &commitments))
+            .map(|share| share.share.id)
+            .collect();
+        assert_eq!(complaints, vec![2]);
+
+        // the dealer publishes the right share
+        let answers = answer_complaints(&shares, &complaints);
+        assert_eq!(
+            resolve_complaints(&complaints, &answers, 3, &commitments),
+            ComplaintResolution::Resolved(vec![shares[1].clone()])
+        );
+    }
+
+    #[test]
+    fn test_cheating_dealer_is_disqualified() {
+        let (mut shares, commitments) = generate_shares(Fp255::from_u64(42), 3, 5).unwrap();
+        shares[1].share.value += Fp255::one();
+        shares[3].blinding += Fp255::one();
+
+        // the dealer sticks to the bad share of party 2 and ignores party 5
+        let complaints = vec![2, 4, 5];
+        let answers = answer_complaints(&shares[..4], &complaints);
+        assert_eq!(
+            resolve_complaints(&complaints, &answers, 3, &commitments),
+            ComplaintResolution::DealerDisqualified(vec![2, 4, 5])
+        );
+    }
+}
@@ -0,0 +1,628 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use rand::CryptoRng;
+
+use crate::errors::ShamirError;
+use crate::ff_ops::PrimeField;
+use crate::interpolate_at_zero;
+use crate::polynomial::{evaluate_polynomial, generate_polynomial_with_rng};
+use crate::share::{Share, check_field_ids, combinations, validate_shares};
+
+/// ```md
+/// A monotone access policy: parties combined with threshold gates
+///
+///   policy := term ("or" term)*
+///   term   := atom ("and" atom)*
+///   atom   := party | "(" policy ")" | k "of" "(" policy ("," policy)* ")"
+///
+/// "and" of n policies is an n of n gate, "or" a 1 of n gate, e.g.
+///
+///   2 of (A, B, C) and D  =  2 of 2 (2 of 3 (A, B, C), D)
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Policy {
+    Party(String),
+    /// Satisfied when at least `threshold` of the children are
+    Threshold {
+        threshold: usize,
+        children: Vec<Policy>,
+    },
+}
+
+impl Policy {
+    pub fn parse(input: &str) -> Result<Self, ShamirError> {
+        let mut parser = Parser {
+            tokens: tokenize(input)?,
+            position: 0,
+        };
+        let policy = parser.policy()?;
+        match parser.peek() {
+            None => Ok(policy),
+            Some(token) => Err(invalid(format!("unexpected {token}"))),
+        }
+    }
+
+    /// Every party named in the policy, sorted, each once
+    pub fn parties(&self) -> Vec<&str> {
+        let mut parties = Vec::new();
+        self.for_each_leaf(&mut |party| parties.push(party));
+        parties.sort_unstable();
+        parties.dedup();
+        parties
+    }
+
+    pub fn can_reconstruct(&self, parties: &[&str]) -> bool {
+        match self {
+            Policy::Party(party) => parties.contains(&party.as_str()),
+            Policy::Threshold {
+                threshold,
+                children,
+            } => {
+                children
+                    .iter()
+                    .filter(|child| child.can_reconstruct(parties))
+                    .count()
+                    >= *threshold
+            }
+        }
+    }
+
+    /// The authorized sets none of whose proper subsets are authorized, each sorted,
+    /// smallest sets first. Every authorized set contains one of them.
+    pub fn minimal_authorized_sets(&self) -> Vec<Vec<&str>> {
+        let mut sets = self.authorized_sets();
+        sets.sort_by(|a, b| a.len().cmp(&b.len()).then_with(|| a.cmp(b)));
+        sets
+    }
+
+    fn authorized_sets(&self) -> Vec<Vec<&str>> {
+        match self {
+            Policy::Party(party) => vec![vec![party.as_str()]],
+            Policy::Threshold {
+                threshold,
+                children,
+            } => {
+                let child_sets: Vec<_> = children.iter().map(Policy::authorized_sets).collect();
+                let mut sets = Vec::new();
+                for chosen in combinations(children.len(), *threshold) {
+                    // one minimal set from each chosen child, in every combination
+                    let mut unions: Vec<Vec<&str>> = vec![vec![]];
+                    for &child in &chosen {
+                        unions = unions
+                            .iter()
+                            .flat_map(|union| {
+                                child_sets[child].iter().map(move |set| {
+                                    let mut union = union.clone();
+                                    union.extend(set);
+                                    union.sort_unstable();
+                                    union.dedup();
+                                    union
+                                })
+                            })
+                            .collect();
+                    }
+                    sets.extend(unions);
+                }
+                minimize(sets)
+            }
+        }
+    }
+
+    /// Benaloh-Leichter sharing: a k of n gate shares its secret with Shamir's scheme of threshold k
+    /// among its children, and every party receives the values that reach its leaves.
+    ///
+    /// Leaves are numbered from 1 in the order they are written, a share's `id` is its leaf,
+    /// and its `threshold` that of the outermost gate.
+    pub fn generate_shares<F: PrimeField>(
+        &self,
+        secret: F,
+    ) -> Result<BTreeMap<String, Vec<Share<F>>>, ShamirError> {
+        self.generate_shares_with_rng(secret, &mut rand::rng())
+    }
+
+    /// `generate_shares` with randomness from `rng`: the same seed gives the same shares
+    pub fn generate_shares_with_rng<F: PrimeField>(
+        &self,
+        secret: F,
+        rng: &mut impl CryptoRng,
+    ) -> Result<BTreeMap<String, Vec<Share<F>>>, ShamirError> {
+        self.check::<F>()?;
+
+        let mut leaves = Vec::new();
+        self.split(secret, rng, &mut leaves)?;
+        let set_id = rng.next_u64();
+
+        let mut shares: BTreeMap<String, Vec<Share<F>>> = BTreeMap::new();
+        for (leaf, (party, value)) in leaves.into_iter().enumerate() {
+            shares.entry(party.to_string()).or_default().push(Share {
+                id: leaf as u32 + 1,
+                value,
+                threshold: self.root_threshold(),
+                set_id,
+            });
+        }
+        Ok(shares)
+    }
+
+    /// Reconstructs from the pooled shares of any parties. Each satisfied gate interpolates
+    /// the first k of its satisfied children, the others are not checked.
+    pub fn reconstruct_secret<F: PrimeField>(&self, shares: &[Share<F>]) -> Result<F, ShamirError> {
+        self.check::<F>()?;
+        validate_shares(shares)?;
+
+        let leaves = self.leaves();
+        let mut values = vec![None; leaves];
+        for share in shares {
+            let slot = values
+                .get_mut(share.id as usize - 1)
+                .ok_or(ShamirError::InvalidShareId(share.id))?;
+            *slot = Some(share.value);
+        }
+
+        self.recover(&mut values.into_iter())
+            .ok_or(ShamirError::UnauthorizedShares)
+    }
+
+    fn split<'a, F: PrimeField>(
+        &'a self,
+        secret: F,
+        rng: &mut impl CryptoRng,
+        leaves: &mut Vec<(&'a str, F)>,
+    ) -> Result<(), ShamirError> {
+        match self {
+            Policy::Party(party) => leaves.push((party, secret)),
+            Policy::Threshold {
+                threshold,
+                children,
+            } => {
+                let polynomial = generate_polynomial_with_rng(secret, *threshold as u32, rng)?;
+                for (i, child) in children.iter().enumerate() {
+                    let value = evaluate_polynomial(&polynomial, F::from_u64(i as u64 + 1));
+                    child.split(value, rng, leaves)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Consumes the leaf values of this subtree, in order
+    fn recover<F: PrimeField>(&self, leaves: &mut impl Iterator<Item = Option<F>>) -> Option<F> {
+        match self {
+            Policy::Party(_) => leaves.next().flatten(),
+            Policy::Threshold {
+                threshold,
+                children,
+            } => {
+                let mut ids = Vec::new();
+                let mut values = Vec::new();
+                for (i, child) in children.iter().enumerate() {
+                    // every child must consume its leaves, satisfied or not
+                    if let Some(value) = child.recover(leaves)
+                        && ids.len() < *threshold
+                    {
+                        ids.push(i as u32 + 1);
+                        values.push(value);
+                    }
+                }
+                (ids.len() == *threshold).then(|| interpolate_at_zero(&ids, values))
+            }
+        }
+    }
+
+    /// Gates must have 1 <= k <= n, and n children must have distinct non-zero ids in F
+    fn check<F: PrimeField>(&self) -> Result<(), ShamirError> {
+        match self {
+            Policy::Party(_) => Ok(()),
+            Policy::Threshold {
+                threshold,
+                children,
+            } => {
+                if *threshold == 0 || *threshold > children.len() {
+                    return Err(invalid(format!(
+                        "threshold {threshold} of {} policies",
+                        children.len()
+                    )));
+                }
+                let ids: Vec<u32> = (1..=children.len() as u32).collect();
+                check_field_ids::<F>(&ids)?;
+                children.iter().try_for_each(Policy::check::<F>)
+            }
+        }
+    }
+
+    fn root_threshold(&self) -> u32 {
+        match self {
+            Policy::Party(_) => 1,
+            Policy::Threshold { threshold, .. } => *threshold as u32,
+        }
+    }
+
+    fn leaves(&self) -> usize {
+        let mut count = 0;
+        self.for_each_leaf(&mut |_| count += 1);
+        count
+    }
+
+    fn for_each_leaf<'a>(&'a self, visit: &mut impl FnMut(&'a str)) {
+        match self {
+            Policy::Party(party) => visit(party),
+            Policy::Threshold { children, .. } => {
+                children.iter().for_each(|child| child.for_each_leaf(visit))
+            }
+        }
+    }
+}
+
+impl fmt::Display for Policy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (threshold, children) = match self {
+            Policy::Party(party) => return f.write_str(party),
+            Policy::Threshold {
+                threshold,
+                children,
+            } => (*threshold, children),
+        };
+
+        let separator = match threshold {
+            1 if children.len() > 1 => " or ",
+            k if k == children.len() && k > 1 => " and ",
+            _ => {
+                write!(f, "{threshold} of ")?;
+                ", "
+            }
+        };
+        f.write_str("(")?;
+        for (i, child) in children.iter().enumerate() {
+            if i > 0 {
+                f.write_str(separator)?;
+            }
+            write!(f, "{child}")?;
+        }
+        f.write_str(")")
+    }
+}
+
+impl std::str::FromStr for Policy {
+    type Err = ShamirError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        Policy::parse(input)
+    }
+}
+
+fn invalid(message: String) -> ShamirError {
+    ShamirError::InvalidPolicy(message)
+}
+
+/// Drops every set that contains another one
+fn minimize(mut sets: Vec<Vec<&str>>) -> Vec<Vec<&str>> {
+    sets.sort_by_key(Vec::len);
+    let mut minimal: Vec<Vec<&str>> = Vec::new();
+    for set in sets {
+        let covered = minimal
+            .iter()
+            .any(|smaller| smaller.iter().all(|party| set.contains(party)));
+        if !covered {
+            minimal.push(set);
+        }
+    }
+    minimal
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Token {
+    Name(String),
+    And,
+    Or,
+    Of,
+    Open,
+    Close,
+    Comma,
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Name(name) => write!(f, "'{name}'"),
+            Token::And => f.write_str("'and'"),
+            Token::Or => f.write_str("'or'"),
+            Token::Of => f.write_str("'of'"),
+            Token::Open => f.write_str("'('"),
+            Token::Close => f.write_str("')'"),
+            Token::Comma => f.write_str("','"),
+        }
+    }
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>, ShamirError> {
+    let is_name = |c: char| c.is_alphanumeric() || "_-.@".contains(c);
+
+    let mut tokens = Vec::new();
+    let mut chars = input.char_indices().peekable();
+    while let Some((start, c)) = chars.next() {
+        let token = match c {
+            '(' => Token::Open,
+            ')' => Token::Close,
+            ',' => Token::Comma,
+            c if c.is_whitespace() => continue,
+            c if is_name(c) => {
+                let mut end = start + c.len_utf8();
+                while let Some(&(i, c)) = chars.peek().filter(|&&(_, c)| is_name(c)) {
+                    end = i + c.len_utf8();
+                    chars.next();
+                }
+                match &input[start..end] {
+                    word if word.eq_ignore_ascii_case("and") => Token::And,
+                    word if word.eq_ignore_ascii_case("or") => Token::Or,
+                    word if word.eq_ignore_ascii_case("of") => Token::Of,
+                    word => Token::Name(word.to_string()),
+                }
+            }
+            c => return Err(invalid(format!("unexpected '{c}'"))),
+        };
+        tokens.push(token);
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Result<Token, ShamirError> {
+        let token = self
+            .tokens
+            .get(self.position)
+            .cloned()
+            .ok_or_else(|| invalid("unexpected end of policy".to_string()))?;
+        self.position += 1;
+        Ok(token)
+    }
+
+    fn expect(&mut self, expected: Token) -> Result<(), ShamirError> {
+        match self.next()? {
+            token if token == expected => Ok(()),
+            token => Err(invalid(format!("expected {expected}, found {token}"))),
+        }
+    }
+
+    fn policy(&mut self) -> Result<Policy, ShamirError> {
+        self.gate(Token::Or, Parser::term, |_| 1)
+    }
+
+    fn term(&mut self) -> Result<Policy, ShamirError> {
+        self.gate(Token::And, Parser::atom, |n| n)
+    }
+
+    /// `operand (operator operand)*`, a single operand is returned as is
+    fn gate(
+        &mut self,
+        operator: Token,
+        operand: fn(&mut Self) -> Result<Policy, ShamirError>,
+        threshold: fn(usize) -> usize,
+    ) -> Result<Policy, ShamirError> {
+        let mut children = vec![operand(self)?];
+        while self.peek() == Some(&operator) {
+            self.position += 1;
+            children.push(operand(self)?);
+        }
+
+        if children.len() == 1 {
+            return Ok(children.remove(0));
+        }
+        Ok(Policy::Threshold {
+            threshold: threshold(children.len()),
+            children,
+        })
+    }
+
+    fn atom(&mut self) -> Result<Policy, ShamirError> {
+        match self.next()? {
+            Token::Open => {
+                let policy = self.policy()?;
+                self.expect(Token::Close)?;
+                Ok(policy)
+            }
+            Token::Name(name) if self.peek() == Some(&Token::Of) => {
+                let threshold = name.parse::<usize>().map_err(|_| {
+                    invalid(format!("expected a threshold before 'of', found '{name}'"))
+                })?;
+                self.position += 1;
+                self.expect(Token::Open)?;
+
+                let mut children = vec![self.policy()?];
+                while self.peek() == Some(&Token::Comma) {
+                    self.position += 1;
+                    children.push(self.policy()?);
+                }
+                self.expect(Token::Close)?;
+
+                if threshold == 0 || threshold > children.len() {
+                    return Err(invalid(format!(
+                        "threshold {threshold} of {} policies",
+                        children.len()
+                    )));
+                }
+                Ok(Policy::Threshold {
+                    threshold,
+                    children,
+                })
+            }
+            Token::Name(name) => Ok(Policy::Party(name)),
+            token => Err(invalid(format!(
+                "expected a party, '(' or a threshold, found {token}"
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    use super::*;
+    use crate::{Fp, Fp255};
+
+    const PRIME: u64 = 997;
+
+    fn party(name: &str) -> Policy {
+        Policy::Party(name.to_string())
+    }
+
+    /// every subset of the parties
+    fn subsets<'a>(parties: &[&'a str]) -> Vec<Vec<&'a str>> {
+        (0u32..1 << parties.len())
+            .map(|mask| {
+                (0..parties.len())
+                    .filter(|&i| mask >> i & 1 == 1)
+                    .map(|i| parties[i])
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn pooled<F: Clone>(
+        shares: &BTreeMap<String, Vec<Share<F>>>,
+        parties: &[&str],
+    ) -> Vec<Share<F>> {
+        parties
+            .iter()
+            .flat_map(|&party| shares[party].clone())
+            .collect()
+    }
+
+    #[test]
+    fn test_parse() {
+        let policy = Policy::parse("2 of (A, B, C) and D").unwrap();
+        assert_eq!(
+            policy,
+            Policy::Threshold {
+                threshold: 2,
+                children: vec![
+                    Policy::Threshold {
+                        threshold: 2,
+                        children: vec![party("A"), party("B"), party("C")],
+                    },
+                    party("D"),
+                ],
+            }
+        );
+        assert_eq!(policy.to_string(), "(2 of (A, B, C) and D)");
+        assert_eq!(policy.parties(), ["A", "B", "C", "D"]);
+
+        // "and" binds tighter than "or"
+        let policy: Policy = "ceo or cfo AND (alice or bob)".parse().unwrap();
+        assert_eq!(policy.to_string(), "(ceo or (cfo and (alice or bob)))");
+        for text in ["A", "1 of (A)", "(A or B) and 3 of (C, D, E, F and G)"] {
+            let policy = Policy::parse(text).unwrap();
+            assert_eq!(Policy::parse(&policy.to_string()), Ok(policy));
+        }
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        for text in [
+            "",
+            "A and",
+            "(A or B",
+            "A B",
+            "0 of (A, B)",
+            "3 of (A, B)",
+            "x of (A, B)",
+            "2 of A, B",
+            "A & B",
+        ] {
+            assert!(
+                matches!(Policy::parse(text), Err(ShamirError::InvalidPolicy(_))),
+                "{text}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_minimal_authorized_sets() {
+        let policy = Policy::parse("2 of (A, B, C) and D").unwrap();
+        assert_eq!(
+            policy.minimal_authorized_sets(),
+            [
+                vec!["A", "B", "D"],
+                vec!["A", "C", "D"],
+                vec!["B", "C", "D"]
+            ]
+        );
+
+        // a party in several branches: A alone covers the first gate
+        let policy = Policy::parse("(A or B and C) and (A or D)").unwrap();
+        assert_eq!(
+            policy.minimal_authorized_sets(),
+            [vec!["A"], vec!["B", "C", "D"]]
+        );
+
+        // an authorized set is exactly one that contains a minimal one
+        let policy = Policy::parse("2 of (A and B, C, 2 of (B, D, E))").unwrap();
+        let minimal = policy.minimal_authorized_sets();
+        for set in subsets(&policy.parties()) {
+            let contains_minimal = minimal
+                .iter()
+                .any(|min| min.iter().all(|party| set.contains(party)));
+            assert_eq!(policy.can_reconstruct(&set), contains_minimal, "{set:?}");
+        }
+    }
+
+    #[test]
+    fn test_share_and_reconstruct() {
+        let policy = Policy::parse("2 of (A, B, C) and D").unwrap();
+        let secret = Fp255::from_u64(0xdead_beef);
+        let shares = policy.generate_shares(secret).unwrap();
+        assert_eq!(shares.len(), 4);
+
+        for set in subsets(&policy.parties()) {
+            let result = policy.reconstruct_secret(&pooled(&shares, &set));
+            if policy.can_reconstruct(&set) {
+                assert_eq!(result, Ok(secret), "{set:?}");
+            } else {
+                assert!(result.is_err(), "{set:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_party_in_several_branches() {
+        type F = Fp<PRIME>;
+        let policy = Policy::parse("(A or B and C) and (A or D)").unwrap();
+        let secret = F::from_u64(123);
+        let shares = policy
+            .generate_shares_with_rng(secret, &mut StdRng::seed_from_u64(1))
+            .unwrap();
+        // A holds a leaf in each branch
+        assert_eq!(shares["A"].len(), 2);
+
+        for set in subsets(&policy.parties()) {
+            let result = policy.reconstruct_secret(&pooled(&shares, &set));
+            assert_eq!(result.is_ok(), policy.can_reconstruct(&set), "{set:?}");
+        }
+        assert_eq!(policy.reconstruct_secret(&shares["A"]), Ok(secret));
+        assert_eq!(
+            policy.reconstruct_secret(&pooled(&shares, &["B", "D"])),
+            Err(ShamirError::UnauthorizedShares)
+        );
+    }
+
+    #[test]
+    fn test_gate_wider_than_the_field() {
+        // 7 children need the ids 1..=7, and 7 = 0 in GF(7)
+        let policy = Policy::parse("4 of (A, B, C, D, E, F, G)").unwrap();
+        assert_eq!(
+            policy.generate_shares(Fp::<7>::from_u64(1)),
+            Err(ShamirError::InvalidShareId(7))
+        );
+    }
+}
@@ -1,6 +1,10 @@
-use rand::RngCore;
+use std::marker::PhantomData;
+use std::ops::{Add, Mul, Neg, Sub};
 
-use crate::ff_ops::{PRIME, mod_add, mod_mul};
+use rand::CryptoRng;
+
+use crate::errors::ShamirError;
+use crate::ff_ops::PrimeField;
 
 /// ```md
 /// Shamir’s secret sharing scheme utilises the fact that for any for t+1 points on the two dimensional
@@ -17,50 +21,574 @@ use crate::ff_ops::{PRIME, mod_add, mod_mul};
 ///
 ///
 /// ```
+///
+/// Fails with `InvalidThreshold` for a threshold of 0, which has no polynomial.
+pub fn generate_polynomial<F: PrimeField>(
+    secret: F,
+    threshold: u32,
+) -> Result<Vec<F>, ShamirError> {
+    generate_polynomial_with_rng(secret, threshold, &mut rand::rng())
+}
+
+/// `generate_polynomial` drawing the coefficients from `rng`, e.g. an HSM-backed generator,
+/// or a seeded DRBG for reproducible tests
+pub fn generate_polynomial_with_rng<F: PrimeField>(
+    secret: F,
+    threshold: u32,
+    rng: &mut impl CryptoRng,
+) -> Result<Vec<F>, ShamirError> {
+    if threshold == 0 {
+        return Err(ShamirError::InvalidThreshold {
+            threshold,
+            total_shares: 0,
+        });
+    }
 
-pub fn generate_polynomial(secret: u64, threshold: u32) -> Vec<u64> {
-    let mut rng = rand::rng();
     let mut coefficients = vec![secret]; // y0
 
     // Generate random coefficients
     for _ in 0..(threshold - 1) {
-        let mut coeff = [0u8; 8];
-        rng.fill_bytes(&mut coeff);
-        coefficients.push(u64::from_le_bytes(coeff) % PRIME);
+        coefficients.push(F::random(rng));
     }
 
-    coefficients
+    Ok(coefficients)
+}
+
+/// Random polynomial of degree threshold - 1 with a zero constant term: f(0) = 0
+///
+/// Shares of it add up to shares of 0, used to re-randomize shares without changing the secret.
+pub fn generate_zero_polynomial<F: PrimeField>(threshold: u32) -> Result<Vec<F>, ShamirError> {
+    generate_polynomial(F::zero(), threshold)
 }
 
 // Evaluates polynomial at point x
-pub fn evaluate_polynomial(coefficients: &[u64], x: u64) -> u64 {
-    let mut result = 0u64;
-    let mut power = 1u64;
+// Horner's rule: f(x) = a_0 + x*(a_1 + x*(a_2 + ...)), one multiplication per coefficient
+pub fn evaluate_polynomial<F: PrimeField>(coefficients: &[F], x: F) -> F {
+    coefficients
+        .iter()
+        .rev()
+        .fold(F::zero(), |acc, &coeff| acc * x + coeff)
+}
+
+/// Below this many points, multi-point evaluation and the subproduct tree fall back to Horner's rule
+const TREE_LEAF_SIZE: usize = 8;
+
+/// ```md
+/// Polynomial over F, coefficients from the constant term up:
+///
+/// f(x) = c_0 + c_1*x + ... + c_d*x^d, c_d != 0
+///
+/// Trailing zero coefficients are trimmed, so the zero polynomial has no coefficients
+/// and every other polynomial has degree `coefficients().len() - 1`.
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Polynomial<F: PrimeField> {
+    coefficients: Vec<F>,
+}
+
+impl<F: PrimeField> Polynomial<F> {
+    pub fn new(mut coefficients: Vec<F>) -> Self {
+        while coefficients.last().is_some_and(|coeff| coeff.is_zero()) {
+            coefficients.pop();
+        }
+        Self { coefficients }
+    }
+
+    pub fn zero() -> Self {
+        Self::new(Vec::new())
+    }
+
+    pub fn constant(value: F) -> Self {
+        Self::new(vec![value])
+    }
+
+    /// Π(x - r) over the roots, the vanishing polynomial of a set of points
+    pub fn from_roots(roots: &[F]) -> Self {
+        roots.iter().fold(Self::constant(F::one()), |acc, &root| {
+            &acc * &Self::new(vec![-root, F::one()])
+        })
+    }
+
+    pub fn coefficients(&self) -> &[F] {
+        &self.coefficients
+    }
+
+    /// c_i, zero above the degree
+    pub fn coefficient(&self, i: usize) -> F {
+        self.coefficients.get(i).copied().unwrap_or(F::zero())
+    }
+
+    /// `None` for the zero polynomial
+    pub fn degree(&self) -> Option<usize> {
+        self.coefficients.len().checked_sub(1)
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.coefficients.is_empty()
+    }
+
+    pub fn leading_coefficient(&self) -> Option<F> {
+        self.coefficients.last().copied()
+    }
+
+    pub fn evaluate(&self, x: F) -> F {
+        evaluate_polynomial(&self.coefficients, x)
+    }
+
+    /// ```md
+    /// Evaluates at every point with a subproduct tree:
+    ///
+    /// f(x_i) = f mod (x - x_i)
+    ///
+    /// The points are split in halves, f is reduced modulo the product Π(x - x_j) of each half,
+    /// and each remainder (of half the degree) is evaluated on its half recursively.
+    /// ```
+    pub fn evaluate_many(&self, points: &[F]) -> Vec<F> {
+        if points.len() <= TREE_LEAF_SIZE {
+            return points.iter().map(|&x| self.evaluate(x)).collect();
+        }
+        let tree = SubproductTree::<F, Schoolbook>::new(points);
+        let mut values = Vec::with_capacity(points.len());
+        tree.evaluate(self, &mut values);
+        values
+    }
+
+    /// ```md
+    /// Lagrange interpolation through the points (x_i, y_i), `None` if two x_i are equal:
+    ///
+    /// M(x) = Π(x - x_j)
+    /// f(x) = Σ(y_i * M(x) / ((x - x_i) * M'(x_i)))
+    ///
+    /// M'(x_i) = Π(x_i - x_j) for j != i is the Lagrange denominator.
+    /// ```
+    pub fn interpolate(points: &[(F, F)]) -> Option<Self> {
+        let xs: Vec<F> = points.iter().map(|&(x, _)| x).collect();
+        let vanishing = Self::from_roots(&xs);
+        let denominators = vanishing.derivative().evaluate_many(&xs);
+
+        let mut result = Self::zero();
+        for (&(x, y), denominator) in points.iter().zip(denominators) {
+            // a repeated x_i is a double root of M, so M'(x_i) = 0
+            let weight = y * denominator.inverse()?;
+            let (basis, _) = vanishing.div_rem(&Self::new(vec![-x, F::one()]))?;
+            result = &result + &basis.scale(weight);
+        }
+        Some(result)
+    }
+
+    /// `(q, r)` with self = q * divisor + r and deg r < deg divisor, `None` when dividing by zero
+    pub fn div_rem(&self, divisor: &Self) -> Option<(Self, Self)> {
+        let divisor_degree = divisor.degree()?;
+        let lead_inverse = divisor.leading_coefficient()?.inverse()?;
+
+        let mut remainder = self.coefficients.clone();
+        if remainder.len() <= divisor_degree {
+            return Some((Self::zero(), self.clone()));
+        }
+
+        let mut quotient = vec![F::zero(); remainder.len() - divisor_degree];
+        for shift in (0..quotient.len()).rev() {
+            let coeff = remainder[shift + divisor_degree] * lead_inverse;
+            quotient[shift] = coeff;
+            for (j, &d) in divisor.coefficients.iter().enumerate() {
+                remainder[shift + j] = remainder[shift + j] - coeff * d;
+            }
+        }
+        remainder.truncate(divisor_degree);
+
+        Some((Self::new(quotient), Self::new(remainder)))
+    }
+
+    /// Monic greatest common divisor (Euclid's algorithm), zero if both are zero
+    pub fn gcd(&self, other: &Self) -> Self {
+        let (mut a, mut b) = (self.clone(), other.clone());
+        while let Some((_, remainder)) = a.div_rem(&b) {
+            a = std::mem::replace(&mut b, remainder);
+        }
+        match a.leading_coefficient().and_then(|lead| lead.inverse()) {
+            Some(inverse) => a.scale(inverse),
+            None => a,
+        }
+    }
+
+    /// f'(x) = Σ(i * c_i * x^(i-1))
+    pub fn derivative(&self) -> Self {
+        Self::new(
+            self.coefficients
+                .iter()
+                .enumerate()
+                .skip(1)
+                .map(|(i, &coeff)| F::from_u64(i as u64) * coeff)
+                .collect(),
+        )
+    }
+
+    /// f(g(x)), by Horner's rule on polynomials
+    pub fn compose(&self, inner: &Self) -> Self {
+        self.coefficients
+            .iter()
+            .rev()
+            .fold(Self::zero(), |acc, &coeff| {
+                &(&acc * inner) + &Self::constant(coeff)
+            })
+    }
+
+    pub fn scale(&self, factor: F) -> Self {
+        Self::new(
+            self.coefficients
+                .iter()
+                .map(|&coeff| coeff * factor)
+                .collect(),
+        )
+    }
+}
+
+impl<F: PrimeField> From<Vec<F>> for Polynomial<F> {
+    fn from(coefficients: Vec<F>) -> Self {
+        Self::new(coefficients)
+    }
+}
+
+impl<F: PrimeField> Add for &Polynomial<F> {
+    type Output = Polynomial<F>;
+
+    fn add(self, rhs: Self) -> Polynomial<F> {
+        let len = self.coefficients.len().max(rhs.coefficients.len());
+        Polynomial::new(
+            (0..len)
+                .map(|i| self.coefficient(i) + rhs.coefficient(i))
+                .collect(),
+        )
+    }
+}
+
+impl<F: PrimeField> Sub for &Polynomial<F> {
+    type Output = Polynomial<F>;
+
+    fn sub(self, rhs: Self) -> Polynomial<F> {
+        let len = self.coefficients.len().max(rhs.coefficients.len());
+        Polynomial::new(
+            (0..len)
+                .map(|i| self.coefficient(i) - rhs.coefficient(i))
+                .collect(),
+        )
+    }
+}
+
+impl<F: PrimeField> Mul for &Polynomial<F> {
+    type Output = Polynomial<F>;
+
+    /// Schoolbook multiplication
+    fn mul(self, rhs: Self) -> Polynomial<F> {
+        if self.is_zero() || rhs.is_zero() {
+            return Polynomial::zero();
+        }
+        let mut product = vec![F::zero(); self.coefficients.len() + rhs.coefficients.len() - 1];
+        for (i, &a) in self.coefficients.iter().enumerate() {
+            for (j, &b) in rhs.coefficients.iter().enumerate() {
+                product[i + j] = product[i + j] + a * b;
+            }
+        }
+        Polynomial::new(product)
+    }
+}
+
+impl<F: PrimeField> Neg for &Polynomial<F> {
+    type Output = Polynomial<F>;
+
+    fn neg(self) -> Polynomial<F> {
+        Polynomial::new(self.coefficients.iter().map(|&coeff| -coeff).collect())
+    }
+}
+
+impl<F: PrimeField> Add for Polynomial<F> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        &self + &rhs
+    }
+}
+
+impl<F: PrimeField> Sub for Polynomial<F> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        &self - &rhs
+    }
+}
+
+impl<F: PrimeField> Mul for Polynomial<F> {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        &self * &rhs
+    }
+}
+
+impl<F: PrimeField> Neg for Polynomial<F> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        -&self
+    }
+}
+
+/// How a subproduct tree multiplies polynomials and reduces them modulo a product of linear factors
+pub(crate) trait TreeArithmetic<F: PrimeField> {
+    fn mul(a: &Polynomial<F>, b: &Polynomial<F>) -> Polynomial<F>;
+
+    fn rem(f: &Polynomial<F>, divisor: &Polynomial<F>) -> Polynomial<F>;
+}
+
+/// Schoolbook multiplication and long division, for any field
+pub(crate) struct Schoolbook;
 
-    for &coeff in coefficients {
-        result = mod_add(result, mod_mul(coeff, power));
-        power = mod_mul(power, x);
+impl<F: PrimeField> TreeArithmetic<F> for Schoolbook {
+    fn mul(a: &Polynomial<F>, b: &Polynomial<F>) -> Polynomial<F> {
+        a * b
     }
 
-    result
+    fn rem(f: &Polynomial<F>, divisor: &Polynomial<F>) -> Polynomial<F> {
+        let (_, remainder) = f
+            .div_rem(divisor)
+            .expect("a product of linear factors is not zero");
+        remainder
+    }
+}
+
+/// Products Π(x - x_i) over the points and recursively over both halves of them
+pub(crate) struct SubproductTree<'a, F: PrimeField, A: TreeArithmetic<F>> {
+    points: &'a [F],
+    product: Polynomial<F>,
+    children: Option<Box<[SubproductTree<'a, F, A>; 2]>>,
+    arithmetic: PhantomData<A>,
+}
+
+impl<'a, F: PrimeField, A: TreeArithmetic<F>> SubproductTree<'a, F, A> {
+    pub(crate) fn new(points: &'a [F]) -> Self {
+        if points.len() <= TREE_LEAF_SIZE {
+            return Self {
+                points,
+                product: Polynomial::from_roots(points),
+                children: None,
+                arithmetic: PhantomData,
+            };
+        }
+        let (left, right) = points.split_at(points.len() / 2);
+        let children = [Self::new(left), Self::new(right)];
+        Self {
+            points,
+            product: A::mul(&children[0].product, &children[1].product),
+            children: Some(Box::new(children)),
+            arithmetic: PhantomData,
+        }
+    }
+
+    /// M(x) = Π(x - x_i) over all points
+    pub(crate) fn product(&self) -> &Polynomial<F> {
+        &self.product
+    }
+
+    /// Pushes f(x_i) for the points of this subtree, f only matters modulo `product`
+    pub(crate) fn evaluate(&self, f: &Polynomial<F>, values: &mut Vec<F>) {
+        let remainder = A::rem(f, &self.product);
+        match &self.children {
+            None => values.extend(self.points.iter().map(|&x| remainder.evaluate(x))),
+            Some(children) => {
+                for child in children.iter() {
+                    child.evaluate(&remainder, values);
+                }
+            }
+        }
+    }
+
+    /// ```md
+    /// Σ(c_i * M(x) / (x - x_i)), from the leaves up:
+    ///
+    /// for M = M_left * M_right the sum splits into left * M_right + right * M_left
+    /// ```
+    pub(crate) fn linear_combination(&self, weights: &[F]) -> Polynomial<F> {
+        match &self.children {
+            None => self.points.iter().zip(weights).enumerate().fold(
+                Polynomial::zero(),
+                |acc, (i, (_, &weight))| {
+                    let others: Vec<F> = self
+                        .points
+                        .iter()
+                        .enumerate()
+                        .filter(|&(j, _)| j != i)
+                        .map(|(_, &x)| x)
+                        .collect();
+                    &acc + &Polynomial::from_roots(&others).scale(weight)
+                },
+            ),
+            Some(children) => {
+                let [left, right] = children.as_ref();
+                let (left_weights, right_weights) = weights.split_at(left.points.len());
+                let left_sum = A::mul(&left.linear_combination(left_weights), &right.product);
+                let right_sum = A::mul(&right.linear_combination(right_weights), &left.product);
+                &left_sum + &right_sum
+            }
+        }
+    }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::ff_ops::{Fp, Fp255};
+
+    type F = Fp<997>;
+
     #[test]
     fn test_polynomial_generation() {
-        let secret = 12345;
+        let secret = F::from_u64(123);
         let threshold = 3;
-        let polynomial = generate_polynomial(secret, threshold);
+        let polynomial = generate_polynomial(secret, threshold).unwrap();
+        assert_eq!(polynomial.len(), threshold as usize);
+        assert_eq!(polynomial[0], secret);
+
+        let secret = Fp255::from_u64(12345);
+        let polynomial = generate_polynomial(secret, threshold).unwrap();
         assert_eq!(polynomial.len(), threshold as usize);
+        assert_eq!(polynomial[0], secret);
+
+        assert_eq!(
+            generate_polynomial(secret, 0),
+            Err(ShamirError::InvalidThreshold {
+                threshold: 0,
+                total_shares: 0
+            })
+        );
+        assert!(generate_zero_polynomial::<F>(0).is_err());
+    }
+
+    #[test]
+    fn test_polynomial_generation_with_rng() {
+        use rand::SeedableRng;
+        use rand::rngs::StdRng;
+
+        let secret = F::from_u64(123);
+        let polynomial =
+            generate_polynomial_with_rng(secret, 4, &mut StdRng::seed_from_u64(1)).unwrap();
+        assert_eq!(polynomial[0], secret);
+        assert_eq!(
+            Ok(polynomial),
+            generate_polynomial_with_rng(secret, 4, &mut StdRng::seed_from_u64(1))
+        );
+    }
+
+    #[test]
+    fn test_zero_polynomial_generation() {
+        let polynomial: Vec<Fp255> = generate_zero_polynomial(4).unwrap();
+        assert_eq!(polynomial.len(), 4);
+        assert_eq!(
+            evaluate_polynomial(&polynomial, Fp255::zero()),
+            Fp255::zero()
+        );
+        assert_ne!(
+            evaluate_polynomial(&polynomial, Fp255::one()),
+            Fp255::zero()
+        );
     }
 
     #[test]
     fn test_polynomial_evaluation() {
-        let coefficients = vec![1, 2, 3]; // polynomial: 3x² + 2x + 1
-        let x = 2;
+        let coefficients = [1, 2, 3].map(F::from_u64); // polynomial: 3x² + 2x + 1
+        let x = F::from_u64(2);
         let result = evaluate_polynomial(&coefficients, x);
-        assert_eq!(result, 17);
+        assert_eq!(result, F::from_u64(17));
+    }
+
+    fn poly(coefficients: &[u64]) -> Polynomial<F> {
+        Polynomial::new(coefficients.iter().map(|&c| F::from_u64(c)).collect())
+    }
+
+    #[test]
+    fn test_trimming_and_degree() {
+        assert_eq!(
+            poly(&[1, 2, 0, 0]).coefficients(),
+            poly(&[1, 2]).coefficients()
+        );
+        assert_eq!(poly(&[1, 2]).degree(), Some(1));
+        assert_eq!(poly(&[0, 0]), Polynomial::zero());
+        assert_eq!(Polynomial::<F>::zero().degree(), None);
+        assert_eq!(poly(&[5]).coefficient(3), F::zero());
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let a = poly(&[1, 2, 3]); // 3x² + 2x + 1
+        let b = poly(&[996, 1]); // x - 1
+
+        assert_eq!(&a + &b, poly(&[0, 3, 3]));
+        assert_eq!(&a - &a, Polynomial::zero());
+        assert_eq!(-&b, poly(&[1, 996]));
+        // (3x² + 2x + 1)(x - 1) = 3x³ - x² - x - 1
+        assert_eq!(&a * &b, poly(&[996, 996, 996, 3]));
+        assert_eq!(a.clone() * Polynomial::zero(), Polynomial::zero());
+
+        let x = F::from_u64(10);
+        assert_eq!((&a * &b).evaluate(x), a.evaluate(x) * b.evaluate(x));
+    }
+
+    #[test]
+    fn test_div_rem() {
+        let mut rng = rand::rng();
+        let a = Polynomial::new((0..9).map(|_| F::random(&mut rng)).collect::<Vec<_>>());
+        let b = Polynomial::new((0..4).map(|_| F::random(&mut rng)).collect::<Vec<_>>());
+
+        let (q, r) = a.div_rem(&b).unwrap();
+        assert_eq!(&(&q * &b) + &r, a);
+        assert!(r.degree() < b.degree());
+
+        assert_eq!(a.div_rem(&Polynomial::zero()), None);
+        assert_eq!(b.div_rem(&a), Some((Polynomial::zero(), b.clone())));
+    }
+
+    #[test]
+    fn test_gcd() {
+        let a = Polynomial::from_roots(&[1, 2, 5].map(F::from_u64));
+        let b = Polynomial::from_roots(&[2, 3, 5].map(F::from_u64)).scale(F::from_u64(7));
+        assert_eq!(a.gcd(&b), Polynomial::from_roots(&[2, 5].map(F::from_u64)));
+        assert_eq!(a.gcd(&Polynomial::zero()), a);
+        assert_eq!(a.gcd(&poly(&[4])), poly(&[1]));
+    }
+
+    #[test]
+    fn test_derivative_and_composition() {
+        // (x³ + 2x)' = 3x² + 2
+        assert_eq!(poly(&[0, 2, 0, 1]).derivative(), poly(&[2, 0, 3]));
+        assert_eq!(poly(&[7]).derivative(), Polynomial::zero());
+
+        // f(g(x)) with f = x² + 1 and g = 2x + 3: 4x² + 12x + 10
+        let f = poly(&[1, 0, 1]);
+        let g = poly(&[3, 2]);
+        assert_eq!(f.compose(&g), poly(&[10, 12, 4]));
+    }
+
+    #[test]
+    fn test_interpolation() {
+        let f = poly(&[5, 0, 3, 1]);
+        let points: Vec<(F, F)> = [1, 2, 3, 4]
+            .map(F::from_u64)
+            .iter()
+            .map(|&x| (x, f.evaluate(x)))
+            .collect();
+        assert_eq!(Polynomial::interpolate(&points), Some(f));
+
+        let repeated = [points[0], points[1], points[0]];
+        assert_eq!(Polynomial::interpolate(&repeated), None);
+    }
+
+    #[test]
+    fn test_evaluate_many() {
+        let mut rng = rand::rng();
+        let f = Polynomial::new((0..40).map(|_| Fp255::random(&mut rng)).collect::<Vec<_>>());
+        let points: Vec<Fp255> = (0..100).map(|_| Fp255::random(&mut rng)).collect();
+
+        let expected: Vec<Fp255> = points.iter().map(|&x| f.evaluate(x)).collect();
+        assert_eq!(f.evaluate_many(&points), expected);
+        assert_eq!(f.evaluate_many(&[]), vec![]);
     }
 }
@@ -0,0 +1,144 @@
+use std::collections::HashMap;
+
+use crate::errors::ShamirError;
+use crate::ff_ops::PrimeField;
+use crate::polynomial::{evaluate_polynomial, generate_zero_polynomial};
+use crate::share::{Share, new_set_id, validate_shares};
+
+/// ```md
+/// Proactive share refresh
+///
+/// Every party j picks a random polynomial z_j(x) of the same degree with z_j(0) = 0
+/// and sends z_j(i) to party i. Party i then replaces its share with
+///
+/// share'_i = f(i) + Σ(z_j(i)) for all parties j
+///
+/// The new shares lie on f'(x) = f(x) + Σ(z_j(x)), and f'(0) = f(0): the secret is unchanged,
+/// but f' is a fresh random polynomial, so shares leaked before the refresh are useless
+/// when combined with shares taken after it.
+/// ```
+///
+/// Party j's contribution: the evaluation of its zero polynomial for every party in `ids`
+pub fn refresh_contribution<F: PrimeField>(
+    ids: &[u32],
+    threshold: u32,
+) -> Result<HashMap<u32, F>, ShamirError> {
+    let polynomial = generate_zero_polynomial(threshold)?;
+    Ok(ids
+        .iter()
+        .map(|&id| (id, evaluate_polynomial(&polynomial, F::from_u64(id as u64))))
+        .collect())
+}
+
+/// Party i's new share: its old share plus every contribution it received
+pub fn apply_refresh<F: PrimeField>(share: F, received: &[F]) -> F {
+    received
+        .iter()
+        .fold(share, |acc, &sub_share| acc + sub_share)
+}
+
+/// Runs one refresh round among all holders of `shares` locally.
+///
+/// The refreshed shares get a new set id, so they are not combined with the old ones by mistake.
+pub fn refresh<F: PrimeField>(shares: &[Share<F>]) -> Result<Vec<Share<F>>, ShamirError> {
+    let threshold = validate_shares(shares)?;
+    let ids: Vec<u32> = shares.iter().map(|share| share.id).collect();
+    let contributions: Vec<HashMap<u32, F>> = ids
+        .iter()
+        .map(|_| refresh_contribution(&ids, threshold))
+        .collect::<Result<_, _>>()?;
+
+    let set_id = new_set_id();
+    Ok(shares
+        .iter()
+        .map(|share| {
+            let received: Vec<F> = contributions
+                .iter()
+                .map(|contribution| contribution[&share.id])
+                .collect();
+            Share {
+                id: share.id,
+                value: apply_refresh(share.value, &received),
+                threshold,
+                set_id,
+            }
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ff_ops::{Fp64, Fp255};
+    use crate::{generate_shares, interpolate_at_zero, reconstruct_secret};
+
+    fn subset<F: PrimeField>(shares: &[Share<F>], ids: &[u32]) -> Vec<Share<F>> {
+        ids.iter()
+            .map(|&id| shares[id as usize - 1].clone())
+            .collect()
+    }
+
+    #[test]
+    fn test_refresh_keeps_the_secret() {
+        let secret = Fp255::from_u64(0xc0ffee);
+        let shares = generate_shares(secret, 3, 5).unwrap();
+        let refreshed = refresh(&shares).unwrap();
+
+        assert_eq!(refreshed.len(), 5);
+        assert!(
+            shares
+                .iter()
+                .zip(&refreshed)
+                .all(|(old, new)| old.value != new.value)
+        );
+        assert_eq!(
+            reconstruct_secret(&subset(&refreshed, &[1, 2, 3])),
+            Ok(secret)
+        );
+        assert_eq!(
+            reconstruct_secret(&subset(&refreshed, &[2, 4, 5])),
+            Ok(secret)
+        );
+
+        let refreshed_twice = refresh(&refreshed).unwrap();
+        assert_eq!(
+            reconstruct_secret(&subset(&refreshed_twice, &[1, 3, 5])),
+            Ok(secret)
+        );
+    }
+
+    #[test]
+    fn test_mixing_old_and_new_shares_fails() {
+        let secret = Fp64::from_u64(0xc0ffee);
+        let old = generate_shares(secret, 3, 5).unwrap();
+        let new = refresh(&old).unwrap();
+
+        let mut mixed = subset(&old, &[1, 2]);
+        mixed.push(new[2].clone());
+        assert_eq!(
+            reconstruct_secret(&mixed),
+            Err(ShamirError::MismatchedShares)
+        );
+
+        // even ignoring the set ids, the values do not interpolate to the secret
+        let ids = [1, 2, 3];
+        let values = [old[0].value, old[1].value, new[2].value];
+        assert_ne!(interpolate_at_zero(&ids, values), secret);
+
+        let ids = [4, 5, 1];
+        let values = [new[3].value, new[4].value, old[0].value];
+        assert_ne!(interpolate_at_zero(&ids, values), secret);
+    }
+
+    #[test]
+    fn test_refresh_needs_a_quorum() {
+        let old = generate_shares(Fp64::from_u64(1), 3, 5).unwrap();
+        assert_eq!(
+            refresh(&old[..2]),
+            Err(ShamirError::InsufficientShares {
+                got: 2,
+                threshold: 3
+            })
+        );
+    }
+}
@@ -0,0 +1,165 @@
+use std::collections::HashMap;
+
+use crate::errors::ShamirError;
+use crate::ff_ops::PrimeField;
+use crate::lagrange_coefficient;
+use crate::polynomial::{evaluate_polynomial, generate_polynomial};
+use crate::share::{Share, check_field_ids, check_parameters, new_set_id, validate_shares};
+
+/// ```md
+/// Share redistribution: move a secret from a t-of-n set to a t'-of-n' set without reconstructing it
+///
+/// A quorum Q of at least t old holders each shares its own share s_i with a fresh polynomial g_i(x)
+/// of degree t' - 1, g_i(0) = s_i, and sends g_i(j) to every new holder j.
+///
+/// New holder j combines what it received with the Lagrange weights of the quorum:
+///
+/// s'_j = Σ(li(0) * g_i(j)) for i in Q
+///
+/// The new shares lie on g(x) = Σ(li(0) * g_i(x)) of degree t' - 1, and
+///
+/// g(0) = Σ(li(0) * s_i) = f(0) = secret
+/// ```
+///
+/// Old holder's side: sub-shares of its own share for every new holder
+pub fn reshare_share<F: PrimeField>(
+    share: F,
+    new_threshold: u32,
+    new_ids: &[u32],
+) -> Result<HashMap<u32, F>, ShamirError> {
+    let polynomial = generate_polynomial(share, new_threshold)?;
+    Ok(new_ids
+        .iter()
+        .map(|&id| (id, evaluate_polynomial(&polynomial, F::from_u64(id as u64))))
+        .collect())
+}
+
+/// New holder's side: combine the sub-shares received from the old quorum, keyed by old holder id
+pub fn combine_sub_shares<F: PrimeField>(sub_shares: &HashMap<u32, F>) -> F {
+    let quorum: Vec<u32> = sub_shares.keys().copied().collect();
+    sub_shares.iter().fold(F::zero(), |acc, (&i, &sub_share)| {
+        acc + lagrange_coefficient::<F>(i, &quorum) * sub_share
+    })
+}
+
+/// Runs the redistribution locally: the holders of `old_shares` form the quorum,
+/// and the result holds the shares of `new_ids` under `new_threshold`, in a new share set
+pub fn redistribute<F: PrimeField>(
+    old_shares: &[Share<F>],
+    new_threshold: u32,
+    new_ids: &[u32],
+) -> Result<Vec<Share<F>>, ShamirError> {
+    // the quorum must reach the old threshold, or the new shares would not encode the secret
+    validate_shares(old_shares)?;
+    let quorum: Vec<u32> = old_shares.iter().map(|share| share.id).collect();
+    check_field_ids::<F>(&quorum)?;
+
+    check_parameters(new_threshold, new_ids.len() as u32)?;
+    check_field_ids::<F>(new_ids)?;
+
+    let sub_shares: Vec<HashMap<u32, F>> = old_shares
+        .iter()
+        .map(|share| reshare_share(share.value, new_threshold, new_ids))
+        .collect::<Result<_, _>>()?;
+
+    let set_id = new_set_id();
+    Ok(new_ids
+        .iter()
+        .map(|&j| {
+            let received: HashMap<u32, F> = quorum
+                .iter()
+                .zip(&sub_shares)
+                .map(|(&i, sub_shares_i)| (i, sub_shares_i[&j]))
+                .collect();
+            Share {
+                id: j,
+                value: combine_sub_shares(&received),
+                threshold: new_threshold,
+                set_id,
+            }
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ff_ops::Fp255;
+    use crate::{generate_shares, interpolate_at_zero, reconstruct_secret};
+
+    fn subset<F: PrimeField>(shares: &[Share<F>], ids: &[u32]) -> Vec<Share<F>> {
+        shares
+            .iter()
+            .filter(|share| ids.contains(&share.id))
+            .cloned()
+            .collect()
+    }
+
+    #[test]
+    fn test_redistribute_3_of_5_to_4_of_7() {
+        let secret = Fp255::from_u64(0xdead_beef);
+        let old = generate_shares(secret, 3, 5).unwrap();
+        let new_ids: Vec<u32> = (11..=17).collect();
+
+        let new = redistribute(&subset(&old, &[1, 3, 4]), 4, &new_ids).unwrap();
+        assert_eq!(new.len(), 7);
+        assert!(new.iter().all(|share| share.threshold == 4));
+        assert_eq!(
+            reconstruct_secret(&subset(&new, &[11, 12, 13, 14])),
+            Ok(secret)
+        );
+        assert_eq!(
+            reconstruct_secret(&subset(&new, &[13, 15, 16, 17])),
+            Ok(secret)
+        );
+
+        // the new threshold is 4: three new shares are not enough
+        let ids = [11, 12, 13];
+        let values = new[..3].iter().map(|share| share.value);
+        assert_ne!(interpolate_at_zero(&ids, values), secret);
+    }
+
+    #[test]
+    fn test_redistribute_with_the_whole_old_set() {
+        let secret = Fp255::from_u64(7);
+        let old = generate_shares(secret, 3, 5).unwrap();
+
+        let new = redistribute(&old, 2, &[1, 2, 3]).unwrap();
+        assert_eq!(reconstruct_secret(&subset(&new, &[1, 3])), Ok(secret));
+    }
+
+    #[test]
+    fn test_redistribute_rejects_invalid_parameters() {
+        let old = generate_shares(Fp255::from_u64(7), 3, 5).unwrap();
+
+        assert_eq!(
+            redistribute(&subset(&old, &[1, 2]), 2, &[1, 2, 3]),
+            Err(ShamirError::InsufficientShares {
+                got: 2,
+                threshold: 3
+            })
+        );
+        assert_eq!(
+            redistribute(&old, 4, &[1, 2, 3]),
+            Err(ShamirError::InvalidThreshold {
+                threshold: 4,
+                total_shares: 3
+            })
+        );
+        assert_eq!(
+            redistribute(&old, 0, &[1, 2, 3]),
+            Err(ShamirError::InvalidThreshold {
+                threshold: 0,
+                total_shares: 3
+            })
+        );
+        assert_eq!(
+            redistribute(&old, 2, &[1, 2, 2]),
+            Err(ShamirError::DuplicateShareId(2))
+        );
+        assert_eq!(
+            redistribute(&old, 2, &[0, 1, 2]),
+            Err(ShamirError::InvalidShareId(0))
+        );
+    }
+}
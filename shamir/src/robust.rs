@@ -0,0 +1,216 @@
+use crate::errors::ShamirError;
+use crate::ff_ops::PrimeField;
+use crate::polynomial::Polynomial;
+use crate::share::{Share, check_field_ids, validate_shares};
+
+/// ```md
+/// Robust reconstruction with the Berlekamp-Welch decoder
+///
+/// Shares are a Reed-Solomon codeword: n evaluations of P(x) of degree k = t - 1.
+/// If at most e = (n - t) / 2 of them are wrong, there is an error locator E(x) of degree e,
+/// monic, whose roots are the ids of the bad shares, and Q(x) = P(x) * E(x) of degree e + k with
+///
+/// Q(x_i) = y_i * E(x_i) for every share i (both sides are 0 at the bad shares)
+///
+/// With E(x) = x^e + Σ(e_j * x^j) and Q(x) = Σ(q_j * x^j) this is a linear system of n equations
+/// in 2e + k + 1 <= n unknowns:
+///
+/// Σ(q_j * x_i^j) - y_i * Σ(e_j * x_i^j) = y_i * x_i^e
+///
+/// Any solution gives P = Q / E, and the shares that disagree with P are the corrupted ones.
+/// ```
+pub fn reconstruct_secret_robust<F: PrimeField>(
+    shares: &[Share<F>],
+) -> Result<(F, Vec<u32>), ShamirError> {
+    let threshold = validate_shares(shares)?;
+    let ids: Vec<u32> = shares.iter().map(|share| share.id).collect();
+    check_field_ids::<F>(&ids)?;
+
+    let n = shares.len();
+    let k = threshold as usize - 1;
+    let max_errors = (n - threshold as usize) / 2;
+
+    let mut points: Vec<(u32, F)> = shares.iter().map(|share| (share.id, share.value)).collect();
+    points.sort_by_key(|&(id, _)| id);
+
+    // unknowns: q_0..q_{e+k}, then e_0..e_{e-1}
+    let q_len = max_errors + k + 1;
+    let mut matrix = Vec::with_capacity(n);
+    let mut rhs = Vec::with_capacity(n);
+    for &(id, y) in &points {
+        let x = F::from_u64(id as u64);
+        let powers = powers_of(x, q_len);
+
+        let mut row = powers.clone();
+        row.extend(powers[..max_errors].iter().map(|&power| -(y * power)));
+        matrix.push(row);
+        rhs.push(y * powers[max_errors]);
+    }
+
+    let (solution, _) = solve_linear_system(matrix, rhs).ok_or(ShamirError::TooManyErrors)?;
+    let q = Polynomial::new(solution[..q_len].to_vec());
+    let mut e = solution[q_len..].to_vec();
+    e.push(F::one());
+
+    let (p, remainder) = q
+        .div_rem(&Polynomial::new(e))
+        .expect("the error locator is monic");
+    if !remainder.is_zero() {
+        return Err(ShamirError::TooManyErrors);
+    }
+
+    let bad_ids: Vec<u32> = points
+        .iter()
+        .filter(|&&(id, y)| p.evaluate(F::from_u64(id as u64)) != y)
+        .map(|&(id, _)| id)
+        .collect();
+    if bad_ids.len() > max_errors {
+        return Err(ShamirError::TooManyErrors);
+    }
+
+    Ok((p.coefficient(0), bad_ids))
+}
+
+fn powers_of<F: PrimeField>(x: F, count: usize) -> Vec<F> {
+    let mut powers = Vec::with_capacity(count);
+    let mut power = F::one();
+    for _ in 0..count {
+        powers.push(power);
+        power = power * x;
+    }
+    powers
+}
+
+/// Gaussian elimination, free variables are set to zero. `None` if the system is inconsistent,
+/// otherwise a solution and the rank: the solution is unique when the rank equals the number of unknowns.
+pub(crate) fn solve_linear_system<F: PrimeField>(
+    mut matrix: Vec<Vec<F>>,
+    mut rhs: Vec<F>,
+) -> Option<(Vec<F>, usize)> {
+    let rows = matrix.len();
+    let cols = matrix.first().map_or(0, Vec::len);
+    let mut pivots = Vec::new();
+
+    let mut row = 0;
+    for col in 0..cols {
+        let Some(pivot) = (row..rows).find(|&r| !matrix[r][col].is_zero()) else {
+            continue;
+        };
+        matrix.swap(row, pivot);
+        rhs.swap(row, pivot);
+
+        let inverse = matrix[row][col].inverse()?;
+        for value in &mut matrix[row][col..] {
+            *value = *value * inverse;
+        }
+        rhs[row] = rhs[row] * inverse;
+
+        let pivot_row = matrix[row].clone();
+        let pivot_rhs = rhs[row];
+        for (r, (current, value)) in matrix.iter_mut().zip(rhs.iter_mut()).enumerate() {
+            let factor = current[col];
+            if r != row && !factor.is_zero() {
+                for (c, &p) in current[col..].iter_mut().zip(&pivot_row[col..]) {
+                    *c = *c - factor * p;
+                }
+                *value = *value - factor * pivot_rhs;
+            }
+        }
+
+        pivots.push(col);
+        row += 1;
+        if row == rows {
+            break;
+        }
+    }
+
+    // a zero row with a non-zero right-hand side has no solution
+    if rhs[row..].iter().any(|value| !value.is_zero()) {
+        return None;
+    }
+
+    let mut solution = vec![F::zero(); cols];
+    for (r, &col) in pivots.iter().enumerate() {
+        solution[col] = rhs[r];
+    }
+    Some((solution, pivots.len()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ff_ops::{Fp, Fp255};
+    use crate::generate_shares;
+
+    #[test]
+    fn test_no_errors() {
+        let secret = Fp255::from_u64(1234);
+        let shares = generate_shares(secret, 3, 7).unwrap();
+        assert_eq!(reconstruct_secret_robust(&shares), Ok((secret, vec![])));
+    }
+
+    #[test]
+    fn test_corrects_up_to_half_the_redundancy() {
+        let secret = Fp255::from_u64(1234);
+        let mut shares = generate_shares(secret, 3, 7).unwrap();
+
+        // (7 - 3) / 2 = 2 errors can be corrected
+        shares[1].value += Fp255::from_u64(1);
+        shares[5].value = Fp255::from_u64(99);
+
+        assert_eq!(reconstruct_secret_robust(&shares), Ok((secret, vec![2, 6])));
+    }
+
+    #[test]
+    fn test_single_error_small_field() {
+        type F = Fp<997>;
+        for secret in [0, 1, 500, 996] {
+            let secret = F::from_u64(secret);
+            let mut shares = generate_shares(secret, 2, 5).unwrap();
+            shares[3].value = shares[3].value + F::one();
+
+            assert_eq!(reconstruct_secret_robust(&shares), Ok((secret, vec![4])));
+        }
+    }
+
+    #[test]
+    fn test_too_many_errors() {
+        let secret = Fp255::from_u64(1234);
+        let mut shares = generate_shares(secret, 3, 7).unwrap();
+        for share in shares
+            .iter_mut()
+            .filter(|share| [1, 4, 7].contains(&share.id))
+        {
+            share.value += Fp255::from_u64(share.id as u64);
+        }
+
+        assert_eq!(
+            reconstruct_secret_robust(&shares),
+            Err(ShamirError::TooManyErrors)
+        );
+    }
+
+    #[test]
+    fn test_error_detected_without_redundancy_to_correct() {
+        let secret = Fp255::from_u64(1234);
+        let mut shares = generate_shares(secret, 3, 4).unwrap();
+        shares[0].value += Fp255::from_u64(1);
+
+        assert_eq!(
+            reconstruct_secret_robust(&shares),
+            Err(ShamirError::TooManyErrors)
+        );
+    }
+
+    #[test]
+    fn test_insufficient_shares() {
+        let shares = generate_shares(Fp255::from_u64(1), 3, 5).unwrap();
+        assert_eq!(
+            reconstruct_secret_robust(&shares[..2]),
+            Err(ShamirError::InsufficientShares {
+                got: 2,
+                threshold: 3
+            })
+        );
+    }
+}
@@ -0,0 +1,419 @@
+use rand::CryptoRng;
+
+use crate::errors::ShamirError;
+use crate::ff_ops::PrimeField;
+use crate::polynomial::{evaluate_polynomial, generate_polynomial_with_rng};
+use crate::share::{Share, check_field_ids, check_parameters, combinations, validate_shares};
+use crate::{generate_shares_with_rng, lagrange_coefficient, reconstruct_secret};
+
+/// A secret sharing scheme over F, with its parameters fixed
+///
+/// Lets protocols be written once for Shamir, additive and replicated shares.
+pub trait SecretSharing<F: PrimeField> {
+    type Share: Clone;
+
+    /// Splits the secret into one share per party, in party order
+    fn share(&self, secret: F) -> Result<Vec<Self::Share>, ShamirError> {
+        self.share_with_rng(secret, &mut rand::rng())
+    }
+
+    fn share_with_rng(
+        &self,
+        secret: F,
+        rng: &mut impl CryptoRng,
+    ) -> Result<Vec<Self::Share>, ShamirError>;
+
+    fn reconstruct(&self, shares: &[Self::Share]) -> Result<F, ShamirError>;
+}
+
+/// `generate_shares` and `reconstruct_secret`: any `threshold` of the `parties` reconstruct
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Shamir {
+    pub threshold: u32,
+    pub parties: u32,
+}
+
+impl<F: PrimeField> SecretSharing<F> for Shamir {
+    type Share = Share<F>;
+
+    fn share_with_rng(
+        &self,
+        secret: F,
+        rng: &mut impl CryptoRng,
+    ) -> Result<Vec<Share<F>>, ShamirError> {
+        generate_shares_with_rng(secret, self.threshold, self.parties, rng)
+    }
+
+    fn reconstruct(&self, shares: &[Share<F>]) -> Result<F, ShamirError> {
+        reconstruct_secret(shares)
+    }
+}
+
+/// ```md
+/// n-of-n additive sharing, as in SPDZ:
+///
+/// secret = s_1 + s_2 + ... + s_n
+///
+/// s_1..s_{n-1} are random, so any n - 1 shares are uniformly random and reveal nothing.
+/// Shares add up locally, like Shamir shares, but all of them are needed.
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Additive {
+    pub parties: u32,
+}
+
+impl<F: PrimeField> SecretSharing<F> for Additive {
+    type Share = Share<F>;
+
+    fn share_with_rng(
+        &self,
+        secret: F,
+        rng: &mut impl CryptoRng,
+    ) -> Result<Vec<Share<F>>, ShamirError> {
+        check_parameters(self.parties, self.parties)?;
+
+        let mut values: Vec<F> = (1..self.parties).map(|_| F::random(rng)).collect();
+        let sum = values.iter().fold(F::zero(), |acc, &value| acc + value);
+        values.push(secret - sum);
+        let set_id = rng.next_u64();
+
+        Ok((1..=self.parties)
+            .zip(values)
+            .map(|(id, value)| Share {
+                id,
+                value,
+                threshold: self.parties,
+                set_id,
+            })
+            .collect())
+    }
+
+    /// Sums the shares, which must be all n of one sharing, e.g. from `shamir_to_additive`
+    fn reconstruct(&self, shares: &[Share<F>]) -> Result<F, ShamirError> {
+        let threshold = validate_shares(shares)?;
+        if threshold != self.parties {
+            return Err(ShamirError::MismatchedShares);
+        }
+        Ok(shares
+            .iter()
+            .fold(F::zero(), |acc, share| acc + share.value))
+    }
+}
+
+/// ```md
+/// Replicated (CNF) sharing: any `threshold` of the `parties` reconstruct
+///
+/// The secret is split additively into one component r_T for every set T of threshold - 1 parties,
+/// and every party outside T receives r_T:
+///
+/// secret = Σ(r_T) for all T with |T| = threshold - 1
+///
+/// threshold - 1 parties all miss the component of their own set, and threshold parties together
+/// hold every component. 2-of-3 gives each party two of the three components r_{1}, r_{2}, r_{3}.
+///
+/// There are C(parties, threshold - 1) components, so this is for small committees; in exchange
+/// every component is held by several parties, which lets reconstruction detect inconsistent shares.
+/// ```
+///
+/// A share holds the components of the sets without its `id`, in the order of `components`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Replicated {
+    pub threshold: u32,
+    pub parties: u32,
+}
+
+impl Replicated {
+    /// The sets T, of 1-based party ids
+    pub fn components(&self) -> Vec<Vec<u32>> {
+        combinations(
+            self.parties as usize,
+            self.threshold.saturating_sub(1) as usize,
+        )
+        .into_iter()
+        .map(|set| set.into_iter().map(|i| i as u32 + 1).collect())
+        .collect()
+    }
+}
+
+impl<F: PrimeField> SecretSharing<F> for Replicated {
+    type Share = Share<Vec<F>>;
+
+    fn share_with_rng(
+        &self,
+        secret: F,
+        rng: &mut impl CryptoRng,
+    ) -> Result<Vec<Share<Vec<F>>>, ShamirError> {
+        check_parameters(self.threshold, self.parties)?;
+        let components = self.components();
+
+        let mut values: Vec<F> = (1..components.len()).map(|_| F::random(rng)).collect();
+        let sum = values.iter().fold(F::zero(), |acc, &value| acc + value);
+        values.push(secret - sum);
+        let set_id = rng.next_u64();
+
+        Ok((1..=self.parties)
+            .map(|id| Share {
+                id,
+                value: components
+                    .iter()
+                    .zip(&values)
+                    .filter(|(set, _)| !set.contains(&id))
+                    .map(|(_, &value)| value)
+                    .collect(),
+                threshold: self.threshold,
+                set_id,
+            })
+            .collect())
+    }
+
+    /// Fails with `InvalidShareValue` if two shares disagree on a component
+    fn reconstruct(&self, shares: &[Share<Vec<F>>]) -> Result<F, ShamirError> {
+        check_parameters(self.threshold, self.parties)?;
+        if validate_shares(shares)? != self.threshold {
+            return Err(ShamirError::MismatchedShares);
+        }
+        let components = self.components();
+
+        let mut values: Vec<Option<F>> = vec![None; components.len()];
+        for share in shares {
+            if share.id > self.parties {
+                return Err(ShamirError::InvalidShareId(share.id));
+            }
+            let held: Vec<usize> = (0..components.len())
+                .filter(|&c| !components[c].contains(&share.id))
+                .collect();
+            if held.len() != share.value.len() {
+                return Err(ShamirError::InvalidShareValue(share.id));
+            }
+
+            for (&c, &value) in held.iter().zip(&share.value) {
+                match values[c] {
+                    Some(known) if known != value => {
+                        return Err(ShamirError::InvalidShareValue(share.id));
+                    }
+                    _ => values[c] = Some(value),
+                }
+            }
+        }
+
+        // threshold parties leave no component out
+        Ok(values
+            .into_iter()
+            .flatten()
+            .fold(F::zero(), |acc, value| acc + value))
+    }
+}
+
+/// ```md
+/// Shamir to additive: t Shamir holders weigh their shares with their Lagrange coefficients
+///
+/// a_i = li(0) * s_i,  Σ(a_i) = f(0) = secret
+///
+/// No interaction is needed. The additive shares keep the ids and set id, with threshold t.
+/// ```
+pub fn shamir_to_additive<F: PrimeField>(
+    shares: &[Share<F>],
+) -> Result<Vec<Share<F>>, ShamirError> {
+    let threshold = validate_shares(shares)?;
+    let ids: Vec<u32> = shares.iter().map(|share| share.id).collect();
+    check_field_ids::<F>(&ids)?;
+
+    // exactly t shares: a larger set would weigh the shares for a polynomial of higher degree,
+    // which still works, but the additive sharing would then need all of them
+    let quorum = &ids[..threshold as usize];
+    Ok(shares[..threshold as usize]
+        .iter()
+        .map(|share| Share {
+            value: lagrange_coefficient::<F>(share.id, quorum) * share.value,
+            ..share.clone()
+        })
+        .collect())
+}
+
+/// ```md
+/// Additive to Shamir: every additive holder i Shamir-shares its own share a_i with g_i(x),
+/// g_i(0) = a_i, and new holder j adds up what it received:
+///
+/// s_j = Σ(g_i(j)),  g(x) = Σ(g_i(x)) has g(0) = Σ(a_i) = secret
+/// ```
+///
+/// Runs the conversion locally. The result holds the shares of `new_ids` under `threshold`, in a new share set.
+pub fn additive_to_shamir<F: PrimeField>(
+    shares: &[Share<F>],
+    threshold: u32,
+    new_ids: &[u32],
+) -> Result<Vec<Share<F>>, ShamirError> {
+    additive_to_shamir_with_rng(shares, threshold, new_ids, &mut rand::rng())
+}
+
+/// `additive_to_shamir` with randomness from `rng`: the same seed gives the same shares
+pub fn additive_to_shamir_with_rng<F: PrimeField>(
+    shares: &[Share<F>],
+    threshold: u32,
+    new_ids: &[u32],
+    rng: &mut impl CryptoRng,
+) -> Result<Vec<Share<F>>, ShamirError> {
+    validate_shares(shares)?;
+    check_parameters(threshold, new_ids.len() as u32)?;
+    check_field_ids::<F>(new_ids)?;
+
+    let polynomials: Vec<Vec<F>> = shares
+        .iter()
+        .map(|share| generate_polynomial_with_rng(share.value, threshold, rng))
+        .collect::<Result<_, _>>()?;
+
+    let set_id = rng.next_u64();
+    Ok(new_ids
+        .iter()
+        .map(|&id| Share {
+            id,
+            value: polynomials.iter().fold(F::zero(), |acc, polynomial| {
+                acc + evaluate_polynomial(polynomial, F::from_u64(id as u64))
+            }),
+            threshold,
+            set_id,
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    use super::*;
+    use crate::{Fp, Fp255};
+
+    const PRIME: u64 = 997;
+
+    type F = Fp<PRIME>;
+
+    /// shares, reconstructs from `quorum` (0-based parties) and returns the result
+    fn round_trip<S: SecretSharing<F>>(
+        scheme: &S,
+        secret: F,
+        quorum: &[usize],
+    ) -> Result<F, ShamirError> {
+        let shares = scheme.share(secret)?;
+        let chosen: Vec<S::Share> = quorum.iter().map(|&i| shares[i].clone()).collect();
+        scheme.reconstruct(&chosen)
+    }
+
+    #[test]
+    fn test_schemes_through_the_trait() {
+        let secret = F::from_u64(321);
+        let shamir = Shamir {
+            threshold: 2,
+            parties: 3,
+        };
+        let additive = Additive { parties: 3 };
+        let replicated = Replicated {
+            threshold: 2,
+            parties: 3,
+        };
+
+        for quorum in [[0, 1], [0, 2], [1, 2]] {
+            assert_eq!(round_trip(&shamir, secret, &quorum), Ok(secret));
+            assert_eq!(round_trip(&replicated, secret, &quorum), Ok(secret));
+            assert!(round_trip(&additive, secret, &quorum).is_err());
+        }
+        assert_eq!(round_trip(&additive, secret, &[2, 0, 1]), Ok(secret));
+        assert_eq!(
+            round_trip(&replicated, secret, &[1]),
+            Err(ShamirError::InsufficientShares {
+                got: 1,
+                threshold: 2
+            })
+        );
+    }
+
+    #[test]
+    fn test_additive_shares_add_up() {
+        let scheme = Additive { parties: 4 };
+        let shares = scheme
+            .share_with_rng(Fp255::from_u64(10), &mut StdRng::seed_from_u64(3))
+            .unwrap();
+        let sum = shares.iter().fold(Fp255::zero(), |acc, s| acc + s.value);
+        assert_eq!(sum, Fp255::from_u64(10));
+
+        // n - 1 shares tell nothing: these are the shares of 10 and of 11 from the same seed
+        let other = scheme
+            .share_with_rng(Fp255::from_u64(11), &mut StdRng::seed_from_u64(3))
+            .unwrap();
+        assert_eq!(shares[..3], other[..3]);
+        assert_ne!(shares[3], other[3]);
+    }
+
+    #[test]
+    fn test_replicated_two_of_three() {
+        let scheme = Replicated {
+            threshold: 2,
+            parties: 3,
+        };
+        assert_eq!(scheme.components(), [vec![1], vec![2], vec![3]]);
+
+        let shares = scheme.share(F::from_u64(5)).unwrap();
+        assert!(shares.iter().all(|share| share.value.len() == 2));
+        // party 1 holds r_{2} and r_{3}, party 2 holds r_{1} and r_{3}
+        assert_eq!(shares[0].value[1], shares[1].value[1]);
+
+        let mut tampered = shares.clone();
+        tampered[1].value[1] = tampered[1].value[1] + F::one();
+        assert_eq!(
+            scheme.reconstruct(&tampered[..2]),
+            Err(ShamirError::InvalidShareValue(2))
+        );
+    }
+
+    #[test]
+    fn test_replicated_three_of_five() {
+        let scheme = Replicated {
+            threshold: 3,
+            parties: 5,
+        };
+        // C(5, 2) components, each party misses the 4 sets it belongs to
+        assert_eq!(scheme.components().len(), 10);
+
+        let secret = Fp255::from_u64(77);
+        let shares = scheme.share(secret).unwrap();
+        assert!(shares.iter().all(|share| share.value.len() == 6));
+        for quorum in combinations(5, 3) {
+            let chosen: Vec<_> = quorum.iter().map(|&i| shares[i].clone()).collect();
+            assert_eq!(scheme.reconstruct(&chosen), Ok(secret));
+        }
+    }
+
+    #[test]
+    fn test_shamir_additive_conversions() {
+        let secret = Fp255::from_u64(2024);
+        let shamir = Shamir {
+            threshold: 3,
+            parties: 5,
+        };
+        let shares = shamir.share(secret).unwrap();
+
+        let additive = shamir_to_additive(&shares[1..]).unwrap();
+        assert_eq!(
+            additive.iter().map(|share| share.id).collect::<Vec<_>>(),
+            [2, 3, 4]
+        );
+        assert_eq!(Additive { parties: 3 }.reconstruct(&additive), Ok(secret));
+        assert!(Additive { parties: 3 }.reconstruct(&additive[..2]).is_err());
+
+        let back = additive_to_shamir(&additive, 2, &[1, 2, 3, 4]).unwrap();
+        assert_eq!(reconstruct_secret(&back[2..]), Ok(secret));
+        assert_eq!(reconstruct_secret(&back[..2]), Ok(secret));
+        assert_ne!(back[0].set_id, shares[0].set_id);
+
+        let from_scratch = Additive { parties: 4 }.share(secret).unwrap();
+        let shamir_shares = additive_to_shamir(&from_scratch, 3, &[1, 2, 3, 4, 5]).unwrap();
+        assert_eq!(shamir.reconstruct(&shamir_shares[2..]), Ok(secret));
+
+        let convert = |seed| {
+            additive_to_shamir_with_rng(&additive, 2, &[1, 2, 3], &mut StdRng::seed_from_u64(seed))
+                .unwrap()
+        };
+        assert_eq!(convert(5), convert(5));
+        assert_ne!(convert(5), convert(6));
+    }
+}
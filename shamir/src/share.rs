@@ -0,0 +1,180 @@
+use rand::RngCore;
+
+use crate::errors::ShamirError;
+use crate::ff_ops::PrimeField;
+
+/// A party's share of a secret
+///
+/// `V` is a field element for `generate_shares`, and bytes for `split_bytes` / `split_bytes_gf256`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Share<V = Vec<u8>> {
+    /// The x-coordinate the dealer's polynomial was evaluated at, never 0
+    pub id: u32,
+    pub value: V,
+    /// Number of shares needed to reconstruct the secret
+    pub threshold: u32,
+    /// Random identifier shared by all shares of one sharing, shares of different sets never combine
+    pub set_id: u64,
+}
+
+pub(crate) fn new_set_id() -> u64 {
+    rand::rng().next_u64()
+}
+
+/// Rejects a threshold of 0 or above the number of shares
+pub(crate) fn check_parameters(threshold: u32, total_shares: u32) -> Result<(), ShamirError> {
+    if threshold == 0 || threshold > total_shares {
+        return Err(ShamirError::InvalidThreshold {
+            threshold,
+            total_shares,
+        });
+    }
+    Ok(())
+}
+
+/// Checks that the shares belong to one set, have distinct non-zero ids and reach the threshold.
+/// Returns the threshold.
+pub(crate) fn validate_shares<V>(shares: &[Share<V>]) -> Result<u32, ShamirError> {
+    let Some(first) = shares.first() else {
+        return Err(ShamirError::InsufficientShares {
+            got: 0,
+            threshold: 1,
+        });
+    };
+
+    for (k, share) in shares.iter().enumerate() {
+        if share.set_id != first.set_id || share.threshold != first.threshold {
+            return Err(ShamirError::MismatchedShares);
+        }
+        if share.id == 0 {
+            return Err(ShamirError::InvalidShareId(share.id));
+        }
+        if shares[..k].iter().any(|other| other.id == share.id) {
+            return Err(ShamirError::DuplicateShareId(share.id));
+        }
+    }
+
+    if shares.len() < first.threshold as usize {
+        return Err(ShamirError::InsufficientShares {
+            got: shares.len(),
+            threshold: first.threshold,
+        });
+    }
+
+    Ok(first.threshold)
+}
+
+/// Checks that the ids are distinct non-zero elements of F as well: in a small field
+/// distinct ids can still collide modulo the prime
+pub(crate) fn check_field_ids<F: PrimeField>(ids: &[u32]) -> Result<(), ShamirError> {
+    let xs: Vec<F> = ids.iter().map(|&id| F::from_u64(id as u64)).collect();
+    for (k, x) in xs.iter().enumerate() {
+        if x.is_zero() {
+            return Err(ShamirError::InvalidShareId(ids[k]));
+        }
+        if xs[..k].contains(x) {
+            return Err(ShamirError::DuplicateShareId(ids[k]));
+        }
+    }
+    Ok(())
+}
+
+/// All k-element subsets of 0..n, each sorted, ordered by their largest element first
+pub(crate) fn combinations(n: usize, k: usize) -> Vec<Vec<usize>> {
+    if k == 0 {
+        return vec![vec![]];
+    }
+    (k - 1..n)
+        .flat_map(|last| {
+            combinations(last, k - 1)
+                .into_iter()
+                .map(move |mut chosen| {
+                    chosen.push(last);
+                    chosen
+                })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn share(id: u32, threshold: u32, set_id: u64) -> Share<u8> {
+        Share {
+            id,
+            value: 0,
+            threshold,
+            set_id,
+        }
+    }
+
+    #[test]
+    fn test_check_parameters() {
+        assert_eq!(check_parameters(3, 5), Ok(()));
+        assert_eq!(check_parameters(5, 5), Ok(()));
+        assert_eq!(
+            check_parameters(0, 5),
+            Err(ShamirError::InvalidThreshold {
+                threshold: 0,
+                total_shares: 5
+            })
+        );
+        assert_eq!(
+            check_parameters(6, 5),
+            Err(ShamirError::InvalidThreshold {
+                threshold: 6,
+                total_shares: 5
+            })
+        );
+    }
+
+    #[test]
+    fn test_validate_shares() {
+        assert_eq!(validate_shares(&[share(1, 2, 7), share(3, 2, 7)]), Ok(2));
+        assert_eq!(
+            validate_shares::<u8>(&[]),
+            Err(ShamirError::InsufficientShares {
+                got: 0,
+                threshold: 1
+            })
+        );
+        assert_eq!(
+            validate_shares(&[share(1, 3, 7), share(3, 3, 7)]),
+            Err(ShamirError::InsufficientShares {
+                got: 2,
+                threshold: 3
+            })
+        );
+        assert_eq!(
+            validate_shares(&[share(1, 2, 7), share(1, 2, 7)]),
+            Err(ShamirError::DuplicateShareId(1))
+        );
+        assert_eq!(
+            validate_shares(&[share(0, 2, 7), share(1, 2, 7)]),
+            Err(ShamirError::InvalidShareId(0))
+        );
+        assert_eq!(
+            validate_shares(&[share(1, 2, 7), share(2, 2, 8)]),
+            Err(ShamirError::MismatchedShares)
+        );
+        assert_eq!(
+            validate_shares(&[share(1, 2, 7), share(2, 3, 7)]),
+            Err(ShamirError::MismatchedShares)
+        );
+    }
+
+    #[test]
+    fn test_check_field_ids() {
+        type F = crate::ff_ops::Fp<7>;
+        assert_eq!(check_field_ids::<F>(&[1, 2, 6]), Ok(()));
+        assert_eq!(
+            check_field_ids::<F>(&[1, 7]),
+            Err(ShamirError::InvalidShareId(7))
+        );
+        assert_eq!(
+            check_field_ids::<F>(&[1, 8]),
+            Err(ShamirError::DuplicateShareId(8))
+        );
+    }
+}
@@ -0,0 +1,901 @@
+use std::collections::BTreeMap;
+
+use hmac::{Hmac, Mac};
+use rand::RngCore;
+use sha2::Sha256;
+
+use crate::errors::ShamirError;
+use crate::gf256::interpolate;
+
+mod wordlist;
+pub use wordlist::WORDLIST;
+
+/// ```md
+/// SLIP-0039: Shamir's Secret-Sharing for Mnemonic Codes
+///
+/// https://github.com/satoshilabs/slips/blob/master/slip-0039.md
+///
+/// The master secret (MS) is encrypted with the passphrase into the encrypted master secret (EMS),
+/// which is shared in two levels over GF(2^8): GT-of-G groups, then T_j-of-N_j members in group j.
+/// Every member share is written as words from a 1024-word list (10 bits each):
+///
+/// id (15 bits) | extendable (1) | iteration exponent (4)            2 words
+/// group index (4) | GT - 1 (4) | G - 1 (4) | member index (4) | T - 1 (4)   2 words
+/// share value, left-padded with zero bits to a multiple of 10 bits
+/// RS1024 checksum                                                   3 words
+/// ```
+const RADIX_BITS: usize = 10;
+const METADATA_WORDS: usize = 4;
+const CHECKSUM_WORDS: usize = 3;
+
+/// 128-bit master secrets give the shortest mnemonics, 20 words
+const MIN_SECRET_BYTES: usize = 16;
+const MIN_MNEMONIC_WORDS: usize =
+    METADATA_WORDS + (MIN_SECRET_BYTES * 8).div_ceil(RADIX_BITS) + CHECKSUM_WORDS;
+
+/// At most 16 groups and 16 members per group: indices and counts take 4 bits
+const MAX_SHARE_COUNT: u8 = 16;
+const MAX_ITERATION_EXPONENT: u8 = 15;
+
+/// x-coordinates of the secret and of the digest share in every sharing with threshold > 1
+const SECRET_INDEX: u8 = 255;
+const DIGEST_INDEX: u8 = 254;
+const DIGEST_BYTES: usize = 4;
+
+const BASE_ITERATION_COUNT: u32 = 10000;
+const ROUND_COUNT: u8 = 4;
+
+const CUSTOMIZATION: &[u8] = b"shamir";
+const CUSTOMIZATION_EXTENDABLE: &[u8] = b"shamir_extendable";
+
+/// A member share, the content of one mnemonic
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MnemonicShare {
+    /// Random identifier common to all shares of a master secret, 15 bits
+    pub identifier: u16,
+    /// Extendable backups do not salt the encryption with the identifier,
+    /// so more share sets can later be made for the same master secret and passphrase
+    pub extendable: bool,
+    /// The encryption runs 10000 * 2^e PBKDF2 iterations
+    pub iteration_exponent: u8,
+    pub group_index: u8,
+    pub group_threshold: u8,
+    pub group_count: u8,
+    pub member_index: u8,
+    pub member_threshold: u8,
+    pub value: Vec<u8>,
+}
+
+impl MnemonicShare {
+    /// Fails if a field does not fit its place in the mnemonic, see `validate`
+    pub fn to_mnemonic(&self) -> Result<String, ShamirError> {
+        self.validate()?;
+        let id_exp = (self.identifier as u32) << 5
+            | (self.extendable as u32) << 4
+            | self.iteration_exponent as u32;
+        let group = (self.group_index as u32) << 16
+            | ((self.group_threshold - 1) as u32) << 12
+            | ((self.group_count - 1) as u32) << 8
+            | (self.member_index as u32) << 4
+            | (self.member_threshold - 1) as u32;
+
+        let mut words = vec![
+            (id_exp >> 10) as u16,
+            (id_exp & 0x3ff) as u16,
+            (group >> 10) as u16,
+            (group & 0x3ff) as u16,
+        ];
+        words.extend(bytes_to_words(&self.value));
+        let checksum = create_checksum(customization(self.extendable), &words);
+        words.extend(checksum);
+
+        Ok(words
+            .iter()
+            .map(|&word| WORDLIST[word as usize])
+            .collect::<Vec<_>>()
+            .join(" "))
+    }
+
+    pub fn from_mnemonic(mnemonic: &str) -> Result<Self, ShamirError> {
+        let words = mnemonic
+            .split_whitespace()
+            .map(|word| {
+                let word = word.to_lowercase();
+                WORDLIST
+                    .binary_search(&word.as_str())
+                    .map(|index| index as u16)
+                    .map_err(|_| ShamirError::UnknownWord(word))
+            })
+            .collect::<Result<Vec<u16>, _>>()?;
+        if words.len() < MIN_MNEMONIC_WORDS {
+            return Err(ShamirError::InvalidEncoding);
+        }
+
+        let id_exp = (words[0] as u32) << 10 | words[1] as u32;
+        let extendable = (id_exp >> 4) & 1 == 1;
+        if !verify_checksum(customization(extendable), &words) {
+            return Err(ShamirError::ChecksumMismatch);
+        }
+
+        let group = (words[2] as u32) << 10 | words[3] as u32;
+        let nibble = |shift: u32| ((group >> shift) & 0xf) as u8;
+        let value_words = &words[METADATA_WORDS..words.len() - CHECKSUM_WORDS];
+        let value = words_to_bytes(value_words).ok_or(ShamirError::InvalidEncoding)?;
+
+        let share = Self {
+            identifier: (id_exp >> 5) as u16,
+            extendable,
+            iteration_exponent: (id_exp & 0xf) as u8,
+            group_index: nibble(16),
+            group_threshold: nibble(12) + 1,
+            group_count: nibble(8) + 1,
+            member_index: nibble(4),
+            member_threshold: nibble(0) + 1,
+            value,
+        };
+        share.validate()?;
+
+        Ok(share)
+    }
+
+    /// The identifier takes 15 bits, the iteration exponent, indices and counts 4, and
+    /// thresholds are stored minus one: 1 <= GT <= G <= 16, group index < G,
+    /// 1 <= T <= 16 and member index < 16
+    fn validate(&self) -> Result<(), ShamirError> {
+        if self.identifier > 0x7fff {
+            return Err(ShamirError::InvalidEncoding);
+        }
+        if self.iteration_exponent > MAX_ITERATION_EXPONENT {
+            return Err(ShamirError::InvalidIterationExponent(
+                self.iteration_exponent,
+            ));
+        }
+        check_parameters(self.group_threshold, self.group_count as usize)?;
+        if self.group_index >= self.group_count {
+            return Err(ShamirError::InvalidShareId(self.group_index as u32));
+        }
+        check_parameters(self.member_threshold, MAX_SHARE_COUNT as usize)?;
+        if self.member_index >= MAX_SHARE_COUNT {
+            return Err(ShamirError::InvalidShareId(self.member_index as u32));
+        }
+        Ok(())
+    }
+}
+
+/// ```md
+/// Encrypts the master secret and shares it in `groups.len()` groups, `group_threshold` of which are needed.
+/// Group j is split into `groups[j] = (T_j, N_j)`: N_j mnemonics, T_j of which recover the group.
+///
+/// Returns the mnemonics of every group. The passphrase must be printable ASCII,
+/// and the master secret at least 16 bytes long with an even length.
+/// ```
+pub fn generate_mnemonics(
+    group_threshold: u8,
+    groups: &[(u8, u8)],
+    master_secret: &[u8],
+    passphrase: &[u8],
+    extendable: bool,
+    iteration_exponent: u8,
+) -> Result<Vec<Vec<String>>, ShamirError> {
+    if master_secret.len() < MIN_SECRET_BYTES || !master_secret.len().is_multiple_of(2) {
+        return Err(ShamirError::InvalidSecretLength(master_secret.len()));
+    }
+    check_passphrase(passphrase)?;
+    if iteration_exponent > MAX_ITERATION_EXPONENT {
+        return Err(ShamirError::InvalidIterationExponent(iteration_exponent));
+    }
+    check_parameters(group_threshold, groups.len())?;
+    for &(member_threshold, member_count) in groups {
+        check_parameters(member_threshold, member_count as usize)?;
+        // a 1-of-n group would just be n copies of the group share
+        if member_threshold == 1 && member_count > 1 {
+            return Err(ShamirError::InvalidThreshold {
+                threshold: 1,
+                total_shares: member_count as u32,
+            });
+        }
+    }
+
+    let mut rng = rand::rng();
+    let identifier = (rng.next_u32() & 0x7fff) as u16;
+    let encrypted = encrypt(
+        master_secret,
+        passphrase,
+        iteration_exponent,
+        identifier,
+        extendable,
+    );
+
+    let group_shares = split_secret(group_threshold, groups.len() as u8, &encrypted);
+    let mnemonics = groups
+        .iter()
+        .zip(group_shares)
+        .enumerate()
+        .map(
+            |(group_index, (&(member_threshold, member_count), group_share))| {
+                split_secret(member_threshold, member_count, &group_share)
+                    .into_iter()
+                    .enumerate()
+                    .map(|(member_index, value)| {
+                        MnemonicShare {
+                            identifier,
+                            extendable,
+                            iteration_exponent,
+                            group_index: group_index as u8,
+                            group_threshold,
+                            group_count: groups.len() as u8,
+                            member_index: member_index as u8,
+                            member_threshold,
+                            value,
+                        }
+                        .to_mnemonic()
+                    })
+                    .collect()
+            },
+        )
+        .collect::<Result<Vec<Vec<String>>, _>>()?;
+
+    Ok(mnemonics)
+}
+
+/// Recovers the master secret from enough mnemonics of enough groups, in any order
+pub fn combine_mnemonics<S: AsRef<str>>(
+    mnemonics: &[S],
+    passphrase: &[u8],
+) -> Result<Vec<u8>, ShamirError> {
+    let shares = mnemonics
+        .iter()
+        .map(|mnemonic| MnemonicShare::from_mnemonic(mnemonic.as_ref()))
+        .collect::<Result<Vec<_>, _>>()?;
+    let Some(first) = shares.first() else {
+        return Err(ShamirError::InsufficientShares {
+            got: 0,
+            threshold: 1,
+        });
+    };
+    check_passphrase(passphrase)?;
+
+    let common = |share: &MnemonicShare| {
+        (
+            share.identifier,
+            share.extendable,
+            share.iteration_exponent,
+            share.group_threshold,
+            share.group_count,
+            share.value.len(),
+        )
+    };
+    if shares.iter().any(|share| common(share) != common(first)) {
+        return Err(ShamirError::MismatchedShares);
+    }
+
+    let mut groups: BTreeMap<u8, Vec<&MnemonicShare>> = BTreeMap::new();
+    for share in &shares {
+        groups.entry(share.group_index).or_default().push(share);
+    }
+
+    // recover every group that has enough members
+    let mut group_shares = Vec::new();
+    let mut incomplete = None;
+    for (&group_index, members) in &groups {
+        let member_threshold = members[0].member_threshold;
+        if members
+            .iter()
+            .any(|share| share.member_threshold != member_threshold)
+        {
+            return Err(ShamirError::MismatchedShares);
+        }
+        let mut points: Vec<(u8, &[u8])> = Vec::with_capacity(members.len());
+        for share in members {
+            if points.iter().any(|&(x, _)| x == share.member_index) {
+                return Err(ShamirError::DuplicateShareId(share.member_index as u32));
+            }
+            points.push((share.member_index, &share.value));
+        }
+
+        if points.len() < member_threshold as usize {
+            incomplete.get_or_insert(ShamirError::InsufficientShares {
+                got: points.len(),
+                threshold: member_threshold as u32,
+            });
+            continue;
+        }
+        group_shares.push((group_index, recover_secret(member_threshold, &points)?));
+    }
+
+    if group_shares.len() < first.group_threshold as usize {
+        return Err(incomplete.unwrap_or(ShamirError::InsufficientShares {
+            got: group_shares.len(),
+            threshold: first.group_threshold as u32,
+        }));
+    }
+    let points: Vec<(u8, &[u8])> = group_shares
+        .iter()
+        .map(|(x, value)| (*x, value.as_slice()))
+        .collect();
+    let encrypted = recover_secret(first.group_threshold, &points)?;
+
+    Ok(decrypt(
+        &encrypted,
+        passphrase,
+        first.iteration_exponent,
+        first.identifier,
+        first.extendable,
+    ))
+}
+
+/// ```md
+/// Four-round Feistel network with PBKDF2-HMAC-SHA256 as round function:
+///
+/// L, R = first and second half of the master secret
+/// F_i(R) = PBKDF2(password = i || passphrase, salt = salt || R, 2500 * 2^e iterations, |R| bytes)
+/// (L, R) = (R, L xor F_i(R)) for i = 0, 1, 2, 3
+/// EMS = R || L
+///
+/// salt = "shamir" || identifier (2 bytes, big-endian), empty for extendable backups
+/// ```
+pub fn encrypt(
+    master_secret: &[u8],
+    passphrase: &[u8],
+    iteration_exponent: u8,
+    identifier: u16,
+    extendable: bool,
+) -> Vec<u8> {
+    feistel(
+        master_secret,
+        passphrase,
+        iteration_exponent,
+        &salt(identifier, extendable),
+        0..ROUND_COUNT,
+    )
+}
+
+/// Runs the rounds of `encrypt` in reverse order
+pub fn decrypt(
+    encrypted: &[u8],
+    passphrase: &[u8],
+    iteration_exponent: u8,
+    identifier: u16,
+    extendable: bool,
+) -> Vec<u8> {
+    feistel(
+        encrypted,
+        passphrase,
+        iteration_exponent,
+        &salt(identifier, extendable),
+        (0..ROUND_COUNT).rev(),
+    )
+}
+
+fn feistel(
+    input: &[u8],
+    passphrase: &[u8],
+    iteration_exponent: u8,
+    salt: &[u8],
+    rounds: impl Iterator<Item = u8>,
+) -> Vec<u8> {
+    let (left, right) = input.split_at(input.len() / 2);
+    let (mut left, mut right) = (left.to_vec(), right.to_vec());
+    let iterations = (BASE_ITERATION_COUNT / ROUND_COUNT as u32) << iteration_exponent;
+
+    for round in rounds {
+        let mut password = vec![round];
+        password.extend_from_slice(passphrase);
+        let mut round_salt = salt.to_vec();
+        round_salt.extend_from_slice(&right);
+
+        let mut f = vec![0u8; right.len()];
+        pbkdf2::pbkdf2_hmac::<Sha256>(&password, &round_salt, iterations, &mut f);
+        let next = left.iter().zip(&f).map(|(l, f)| l ^ f).collect();
+        left = std::mem::replace(&mut right, next);
+    }
+
+    right.extend_from_slice(&left);
+    right
+}
+
+fn salt(identifier: u16, extendable: bool) -> Vec<u8> {
+    if extendable {
+        return Vec::new();
+    }
+    let mut salt = CUSTOMIZATION.to_vec();
+    salt.extend_from_slice(&identifier.to_be_bytes());
+    salt
+}
+
+/// ```md
+/// Shares the secret with threshold T among `count` shares at x = 0, 1, ...
+///
+/// For T > 1 the polynomial is fixed by T - 2 random shares at x = [0, T - 2),
+/// the secret at x = 255 and a digest share at x = 254:
+///
+/// digest share = HMAC-SHA256(key = R, secret)[..4] || R, with R random
+///
+/// so that a wrong set of shares is detected when recovering.
+/// ```
+fn split_secret(threshold: u8, count: u8, secret: &[u8]) -> Vec<Vec<u8>> {
+    if threshold == 1 {
+        return vec![secret.to_vec(); count as usize];
+    }
+
+    let mut rng = rand::rng();
+    let mut shares: Vec<Vec<u8>> = (0..threshold - 2)
+        .map(|_| {
+            let mut share = vec![0u8; secret.len()];
+            rng.fill_bytes(&mut share);
+            share
+        })
+        .collect();
+
+    let mut random_part = vec![0u8; secret.len() - DIGEST_BYTES];
+    rng.fill_bytes(&mut random_part);
+    let mut digest_share = digest(&random_part, secret).to_vec();
+    digest_share.extend_from_slice(&random_part);
+
+    let mut base: Vec<(u8, &[u8])> = shares
+        .iter()
+        .enumerate()
+        .map(|(x, share)| (x as u8, share.as_slice()))
+        .collect();
+    base.push((DIGEST_INDEX, &digest_share));
+    base.push((SECRET_INDEX, secret));
+
+    let derived: Vec<Vec<u8>> = (threshold - 2..count)
+        .map(|x| interpolate(&base, x))
+        .collect();
+    shares.extend(derived);
+    shares
+}
+
+/// Interpolates the secret at x = 255 and checks it against the digest share at x = 254
+fn recover_secret(threshold: u8, points: &[(u8, &[u8])]) -> Result<Vec<u8>, ShamirError> {
+    let points = &points[..threshold as usize];
+    if threshold == 1 {
+        return Ok(points[0].1.to_vec());
+    }
+
+    let secret = interpolate(points, SECRET_INDEX);
+    let digest_share = interpolate(points, DIGEST_INDEX);
+    let (expected, random_part) = digest_share.split_at(DIGEST_BYTES);
+    let actual = digest(random_part, &secret);
+
+    // compare without an early exit
+    let difference = expected
+        .iter()
+        .zip(&actual)
+        .fold(0u8, |acc, (a, b)| acc | (a ^ b));
+    if difference != 0 {
+        return Err(ShamirError::InvalidDigest);
+    }
+
+    Ok(secret)
+}
+
+fn digest(random_part: &[u8], secret: &[u8]) -> [u8; DIGEST_BYTES] {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(random_part).expect("HMAC accepts keys of any length");
+    mac.update(secret);
+    let mut digest = [0u8; DIGEST_BYTES];
+    digest.copy_from_slice(&mac.finalize().into_bytes()[..DIGEST_BYTES]);
+    digest
+}
+
+fn check_parameters(threshold: u8, count: usize) -> Result<(), ShamirError> {
+    if threshold == 0 || threshold as usize > count || count > MAX_SHARE_COUNT as usize {
+        return Err(ShamirError::InvalidThreshold {
+            threshold: threshold as u32,
+            total_shares: count as u32,
+        });
+    }
+    Ok(())
+}
+
+fn check_passphrase(passphrase: &[u8]) -> Result<(), ShamirError> {
+    if passphrase.iter().all(|byte| (32..=126).contains(byte)) {
+        Ok(())
+    } else {
+        Err(ShamirError::InvalidPassphrase)
+    }
+}
+
+fn customization(extendable: bool) -> &'static [u8] {
+    if extendable {
+        CUSTOMIZATION_EXTENDABLE
+    } else {
+        CUSTOMIZATION
+    }
+}
+
+/// Big-endian bits of `bytes`, left-padded with zeros to a multiple of 10 bits
+fn bytes_to_words(bytes: &[u8]) -> Vec<u16> {
+    let word_count = (bytes.len() * 8).div_ceil(RADIX_BITS);
+    let mut words = Vec::with_capacity(word_count);
+
+    // start with the padding bits already in the accumulator
+    let mut acc = 0u32;
+    let mut bits = word_count * RADIX_BITS - bytes.len() * 8;
+    for &byte in bytes {
+        acc = acc << 8 | byte as u32;
+        bits += 8;
+        if bits >= RADIX_BITS {
+            bits -= RADIX_BITS;
+            words.push((acc >> bits) as u16);
+            acc &= (1 << bits) - 1;
+        }
+    }
+
+    words
+}
+
+/// Inverse of `bytes_to_words`, `None` if the padding is longer than a byte or not zero
+fn words_to_bytes(words: &[u16]) -> Option<Vec<u8>> {
+    // the value has an even number of bytes
+    let padding = words.len() * RADIX_BITS % 16;
+    if padding > 8 {
+        return None;
+    }
+
+    let mut bytes = Vec::with_capacity((words.len() * RADIX_BITS - padding) / 8);
+    let mut acc = 0u32;
+    let mut bits = 0;
+    for (i, &word) in words.iter().enumerate() {
+        acc = acc << RADIX_BITS | word as u32;
+        bits += RADIX_BITS;
+        if i == 0 {
+            if acc >> (RADIX_BITS - padding) != 0 {
+                return None;
+            }
+            bits -= padding;
+        }
+        while bits >= 8 {
+            bits -= 8;
+            bytes.push((acc >> bits) as u8);
+            acc &= (1 << bits) - 1;
+        }
+    }
+
+    Some(bytes)
+}
+
+/// ```md
+/// RS1024: a Reed-Solomon code over GF(1024) (BCH-style, as in Bech32) that detects
+/// any error affecting at most 3 words, and fewer than 1 in 10^9 other errors.
+/// ```
+fn rs1024_polymod(values: impl IntoIterator<Item = u32>) -> u32 {
+    const GENERATOR: [u32; 10] = [
+        0xe0e040, 0x1c1c080, 0x3838100, 0x7070200, 0xe0e0009, 0x1c0c2412, 0x38086c24, 0x3090fc48,
+        0x21b1f890, 0x3f3f120,
+    ];
+
+    let mut checksum = 1u32;
+    for value in values {
+        let top = checksum >> 20;
+        checksum = (checksum & 0xfffff) << 10 ^ value;
+        for (i, generator) in GENERATOR.iter().enumerate() {
+            if (top >> i) & 1 == 1 {
+                checksum ^= generator;
+            }
+        }
+    }
+    checksum
+}
+
+fn create_checksum(customization: &[u8], words: &[u16]) -> [u16; CHECKSUM_WORDS] {
+    let values = customization
+        .iter()
+        .map(|&byte| byte as u32)
+        .chain(words.iter().map(|&word| word as u32))
+        .chain([0; CHECKSUM_WORDS]);
+    let polymod = rs1024_polymod(values) ^ 1;
+    [
+        (polymod >> 20) as u16 & 0x3ff,
+        (polymod >> 10) as u16 & 0x3ff,
+        polymod as u16 & 0x3ff,
+    ]
+}
+
+fn verify_checksum(customization: &[u8], words: &[u16]) -> bool {
+    let values = customization
+        .iter()
+        .map(|&byte| byte as u32)
+        .chain(words.iter().map(|&word| word as u32));
+    rs1024_polymod(values) == 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // from the official SLIP-39 test vectors (vectors.json), passphrase "TREZOR"
+    const PASSPHRASE: &[u8] = b"TREZOR";
+
+    fn hex(secret: &str) -> Vec<u8> {
+        hex::decode(secret).unwrap()
+    }
+
+    #[test]
+    fn test_wordlist() {
+        assert!(WORDLIST.windows(2).all(|pair| pair[0] < pair[1]));
+        assert!(WORDLIST.iter().all(|word| (4..=8).contains(&word.len())));
+        // the first four letters identify every word
+        assert!(WORDLIST.windows(2).all(|pair| pair[0][..4] != pair[1][..4]));
+    }
+
+    #[test]
+    fn test_vector_without_sharing() {
+        let mnemonic = "duckling enlarge academic academic agency result length solution fridge kidney coal piece deal husband erode duke ajar critical decision keyboard";
+        assert_eq!(
+            combine_mnemonics(&[mnemonic], PASSPHRASE),
+            Ok(hex("bb54aac4b89dc868ba37d9cc21b2cece"))
+        );
+
+        let share = MnemonicShare::from_mnemonic(mnemonic).unwrap();
+        assert_eq!((share.group_threshold, share.member_threshold), (1, 1));
+        assert_eq!(share.to_mnemonic(), Ok(mnemonic.to_string()));
+
+        let mnemonic = "theory painting academic academic armed sweater year military elder discuss acne wildlife boring employer fused large satoshi bundle carbon diagnose anatomy hamster leaves tracks paces beyond phantom capital marvel lips brave detect luck";
+        assert_eq!(
+            combine_mnemonics(&[mnemonic], PASSPHRASE),
+            Ok(hex(
+                "989baf9dcaad5b10ca33dfd8cc75e42477025dce88ae83e75a230086a0e00e92"
+            ))
+        );
+    }
+
+    #[test]
+    fn test_vector_invalid_checksum_and_padding() {
+        let bad_checksum = "duckling enlarge academic academic agency result length solution fridge kidney coal piece deal husband erode duke ajar critical decision kidney";
+        assert_eq!(
+            combine_mnemonics(&[bad_checksum], PASSPHRASE),
+            Err(ShamirError::ChecksumMismatch)
+        );
+
+        let bad_padding = "duckling enlarge academic academic email result length solution fridge kidney coal piece deal husband erode duke ajar music cargo fitness";
+        assert_eq!(
+            combine_mnemonics(&[bad_padding], PASSPHRASE),
+            Err(ShamirError::InvalidEncoding)
+        );
+    }
+
+    #[test]
+    fn test_vector_basic_sharing() {
+        let mnemonics = [
+            "shadow pistol academic always adequate wildlife fancy gross oasis cylinder mustang wrist rescue view short owner flip making coding armed",
+            "shadow pistol academic acid actress prayer class unknown daughter sweater depict flip twice unkind craft early superior advocate guest smoking",
+        ];
+        assert_eq!(
+            combine_mnemonics(&mnemonics, PASSPHRASE),
+            Ok(hex("b43ceb7e57a0ea8766221624d01b0864"))
+        );
+        assert_eq!(
+            combine_mnemonics(&mnemonics[1..], PASSPHRASE),
+            Err(ShamirError::InsufficientShares {
+                got: 1,
+                threshold: 2
+            })
+        );
+
+        let mnemonics = [
+            "humidity disease academic always aluminum jewelry energy woman receiver strategy amuse duckling lying evidence network walnut tactics forget hairy rebound impulse brother survive clothes stadium mailman rival ocean reward venture always armed unwrap",
+            "humidity disease academic agency actress jacket gross physics cylinder solution fake mortgage benefit public busy prepare sharp friar change work slow purchase ruler again tricycle involve viral wireless mixture anatomy desert cargo upgrade",
+        ];
+        assert_eq!(
+            combine_mnemonics(&mnemonics, PASSPHRASE),
+            Ok(hex(
+                "c938b319067687e990e05e0da0ecce1278f75ff58d9853f19dcaeed5de104aae"
+            ))
+        );
+    }
+
+    #[test]
+    fn test_vector_different_identifiers() {
+        let mnemonics = [
+            "adequate smoking academic acid debut wine petition glen cluster slow rhyme slow simple epidemic rumor junk tracks treat olympic tolerate",
+            "adequate stay academic agency agency formal party ting frequent learn upstairs remember smear leaf damage anatomy ladle market hush corner",
+        ];
+        assert_eq!(
+            combine_mnemonics(&mnemonics, PASSPHRASE),
+            Err(ShamirError::MismatchedShares)
+        );
+    }
+
+    #[test]
+    fn test_vector_group_sharing() {
+        let mnemonics = [
+            "eraser senior decision roster beard treat identify grumpy salt index fake aviation theater cubic bike cause research dragon emphasis counter",
+            "eraser senior ceramic snake clay various huge numb argue hesitate auction category timber browser greatest hanger petition script leaf pickup",
+            "eraser senior ceramic shaft dynamic become junior wrist silver peasant force math alto coal amazing segment yelp velvet image paces",
+            "eraser senior ceramic round column hawk trust auction smug shame alive greatest sheriff living perfect corner chest sled fumes adequate",
+            "eraser senior decision smug corner ruin rescue cubic angel tackle skin skunk program roster trash rumor slush angel flea amazing",
+        ];
+        assert_eq!(
+            combine_mnemonics(&mnemonics, PASSPHRASE),
+            Ok(hex("7c3397a292a5941682d7a4ae2d898d11"))
+        );
+    }
+
+    #[test]
+    fn test_vector_extendable() {
+        let mnemonic = "testify swimming academic academic column loyalty smear include exotic bedroom exotic wrist lobe cover grief golden smart junior estimate learn";
+        assert!(MnemonicShare::from_mnemonic(mnemonic).unwrap().extendable);
+        assert_eq!(
+            combine_mnemonics(&[mnemonic], PASSPHRASE),
+            Ok(hex("1679b4516e0ee5954351d288a838f45e"))
+        );
+    }
+
+    #[test]
+    fn test_two_level_round_trip() {
+        let master_secret = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ012345";
+        let groups = [(1, 1), (2, 3), (3, 5)];
+        let mnemonics =
+            generate_mnemonics(2, &groups, master_secret, b"passphrase", false, 0).unwrap();
+        assert_eq!(
+            mnemonics.iter().map(Vec::len).collect::<Vec<_>>(),
+            [1, 3, 5]
+        );
+        assert!(
+            mnemonics
+                .iter()
+                .flatten()
+                .all(|m| m.split(' ').count() == 33)
+        );
+
+        let chosen = [
+            &mnemonics[2][4],
+            &mnemonics[1][2],
+            &mnemonics[2][0],
+            &mnemonics[1][0],
+            &mnemonics[2][1],
+        ];
+        assert_eq!(
+            combine_mnemonics(&chosen, b"passphrase").unwrap(),
+            master_secret
+        );
+        assert_eq!(
+            combine_mnemonics(
+                &[&mnemonics[0][0], &mnemonics[1][1], &mnemonics[1][2]],
+                b"passphrase"
+            )
+            .unwrap(),
+            master_secret
+        );
+
+        // a wrong passphrase silently gives another secret, by design
+        assert_ne!(
+            combine_mnemonics(&chosen, b"").unwrap(),
+            master_secret.to_vec()
+        );
+
+        // one group is not enough
+        assert_eq!(
+            combine_mnemonics(&mnemonics[2][..3], b"passphrase"),
+            Err(ShamirError::InsufficientShares {
+                got: 1,
+                threshold: 2
+            })
+        );
+    }
+
+    #[test]
+    fn test_extendable_round_trip() {
+        let master_secret = hex("0011223344556677889900aabbccddeeff");
+        assert_eq!(
+            generate_mnemonics(1, &[(2, 3)], &master_secret, b"", true, 1),
+            Err(ShamirError::InvalidSecretLength(17))
+        );
+
+        let master_secret = &master_secret[1..];
+        let mnemonics = generate_mnemonics(1, &[(2, 3)], master_secret, b"", true, 1).unwrap();
+        assert_eq!(
+            combine_mnemonics(&mnemonics[0][1..], b"").unwrap(),
+            master_secret
+        );
+    }
+
+    #[test]
+    fn test_corrupted_share_fails_digest() {
+        let master_secret = [7u8; 16];
+        let mnemonics = generate_mnemonics(1, &[(3, 5)], &master_secret, b"", false, 0).unwrap();
+
+        let mut share = MnemonicShare::from_mnemonic(&mnemonics[0][1]).unwrap();
+        share.value[3] ^= 1;
+        let corrupted = [
+            mnemonics[0][0].clone(),
+            share.to_mnemonic().unwrap(),
+            mnemonics[0][2].clone(),
+        ];
+        assert_eq!(
+            combine_mnemonics(&corrupted, b""),
+            Err(ShamirError::InvalidDigest)
+        );
+    }
+
+    #[test]
+    fn test_invalid_parameters() {
+        let secret = [0u8; 16];
+        assert_eq!(
+            generate_mnemonics(1, &[(1, 3)], &secret, b"", false, 0),
+            Err(ShamirError::InvalidThreshold {
+                threshold: 1,
+                total_shares: 3
+            })
+        );
+        assert_eq!(
+            generate_mnemonics(3, &[(1, 1), (2, 2)], &secret, b"", false, 0),
+            Err(ShamirError::InvalidThreshold {
+                threshold: 3,
+                total_shares: 2
+            })
+        );
+        assert_eq!(
+            generate_mnemonics(1, &[(2, 17)], &secret, b"", false, 0),
+            Err(ShamirError::InvalidThreshold {
+                threshold: 2,
+                total_shares: 17
+            })
+        );
+        assert_eq!(
+            generate_mnemonics(1, &[(1, 1)], &secret, "pässword".as_bytes(), false, 0),
+            Err(ShamirError::InvalidPassphrase)
+        );
+        assert_eq!(
+            MnemonicShare::from_mnemonic("duckling enlarge academic bitcoin"),
+            Err(ShamirError::UnknownWord("bitcoin".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_invalid_share_fields() {
+        let mnemonics =
+            generate_mnemonics(2, &[(2, 3), (2, 2)], &[1u8; 16], b"", false, 0).unwrap();
+        let share = MnemonicShare::from_mnemonic(&mnemonics[1][0]).unwrap();
+
+        let zero_threshold = MnemonicShare {
+            member_threshold: 0,
+            ..share.clone()
+        };
+        assert_eq!(
+            zero_threshold.to_mnemonic(),
+            Err(ShamirError::InvalidThreshold {
+                threshold: 0,
+                total_shares: 16
+            })
+        );
+        let zero_groups = MnemonicShare {
+            group_threshold: 0,
+            ..share.clone()
+        };
+        assert!(zero_groups.to_mnemonic().is_err());
+
+        // group index 2 of 2 groups: encodable, but not a valid share
+        let outside = MnemonicShare {
+            group_index: 2,
+            ..share.clone()
+        };
+        assert_eq!(outside.to_mnemonic(), Err(ShamirError::InvalidShareId(2)));
+
+        // a well-formed mnemonic with group index 2 of 2 groups is rejected when read
+        let mut words: Vec<u16> = mnemonics[1][0]
+            .split_whitespace()
+            .map(|word| WORDLIST.binary_search(&word).unwrap() as u16)
+            .collect();
+        words[2] = (words[2] & !(0xf << 6)) | (2 << 6);
+        words.truncate(words.len() - CHECKSUM_WORDS);
+        let checksum = create_checksum(customization(false), &words);
+        words.extend(checksum);
+        let mnemonic: Vec<&str> = words.iter().map(|&word| WORDLIST[word as usize]).collect();
+        assert_eq!(
+            MnemonicShare::from_mnemonic(&mnemonic.join(" ")),
+            Err(ShamirError::InvalidShareId(2))
+        );
+    }
+
+    #[test]
+    fn test_words_bytes_round_trip() {
+        for len in [16, 18, 32] {
+            let bytes: Vec<u8> = (0..len as u8).map(|i| i.wrapping_mul(37) ^ 0xa5).collect();
+            assert_eq!(words_to_bytes(&bytes_to_words(&bytes)), Some(bytes));
+        }
+    }
+}
@@ -0,0 +1,296 @@
+use rand::CryptoRng;
+
+use crate::errors::ShamirError;
+use crate::ff_ops::PrimeField;
+use crate::share::check_parameters;
+
+/// ```md
+/// SPDZ-style authenticated additive shares
+///
+/// A global MAC key α is additively shared, party i holding α_i. A value x is shared as
+///
+/// x = Σ(x_i),  α * x = Σ(m_i)
+///
+/// Everything is linear in (x_i, m_i), so parties add and scale both locally. When x is opened,
+/// every party publishes σ_i = m_i - α_i * x, and Σ(σ_i) = α * x - α * x = 0.
+/// A party that publishes x_i + δ instead makes the opened value x + δ, and then
+///
+/// Σ(σ_i) = -α * δ != 0
+///
+/// unless it guesses α: the tampering is caught with probability 1 - 1/p.
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AuthenticatedShare<F> {
+    pub id: u32,
+    pub value: F,
+    pub mac: F,
+}
+
+impl<F: PrimeField> AuthenticatedShare<F> {
+    pub fn add(&self, other: &Self) -> Self {
+        AuthenticatedShare {
+            id: self.id,
+            value: self.value + other.value,
+            mac: self.mac + other.mac,
+        }
+    }
+
+    pub fn sub(&self, other: &Self) -> Self {
+        AuthenticatedShare {
+            id: self.id,
+            value: self.value - other.value,
+            mac: self.mac - other.mac,
+        }
+    }
+
+    pub fn multiply_by_constant(&self, constant: F) -> Self {
+        AuthenticatedShare {
+            id: self.id,
+            value: self.value * constant,
+            mac: self.mac * constant,
+        }
+    }
+
+    /// x + c: party 1 adds c to its value, and every party adds α_i * c to its MAC
+    pub fn add_constant(&self, constant: F, key_share: F) -> Self {
+        AuthenticatedShare {
+            id: self.id,
+            value: if self.id == 1 {
+                self.value + constant
+            } else {
+                self.value
+            },
+            mac: self.mac + key_share * constant,
+        }
+    }
+}
+
+/// One party's shares of an authenticated Beaver triple, c = a * b
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AuthenticatedTriple<F> {
+    pub a: AuthenticatedShare<F>,
+    pub b: AuthenticatedShare<F>,
+    pub c: AuthenticatedShare<F>,
+}
+
+/// Trusted dealer for the preprocessing phase: holds the MAC key and hands out
+/// authenticated shares and triples to parties 1..=parties
+pub struct Dealer<F> {
+    parties: u32,
+    key: F,
+    key_shares: Vec<F>,
+}
+
+impl<F: PrimeField> Dealer<F> {
+    pub fn new(parties: u32, rng: &mut impl CryptoRng) -> Result<Self, ShamirError> {
+        check_parameters(parties, parties)?;
+        let key_shares: Vec<F> = (0..parties).map(|_| F::random(rng)).collect();
+        let key = key_shares.iter().fold(F::zero(), |acc, &share| acc + share);
+        Ok(Dealer {
+            parties,
+            key,
+            key_shares,
+        })
+    }
+
+    /// α_i for every party, in party order
+    pub fn key_shares(&self) -> &[F] {
+        &self.key_shares
+    }
+
+    /// Authenticated shares of `value`, in party order
+    pub fn share(&self, value: F, rng: &mut impl CryptoRng) -> Vec<AuthenticatedShare<F>> {
+        let values = additive_split(value, self.parties, rng);
+        let macs = additive_split(self.key * value, self.parties, rng);
+        (1..=self.parties)
+            .zip(values.into_iter().zip(macs))
+            .map(|(id, (value, mac))| AuthenticatedShare { id, value, mac })
+            .collect()
+    }
+
+    /// An authenticated triple, in party order
+    pub fn triple(&self, rng: &mut impl CryptoRng) -> Vec<AuthenticatedTriple<F>> {
+        let a = F::random(rng);
+        let b = F::random(rng);
+        let a_shares = self.share(a, rng);
+        let b_shares = self.share(b, rng);
+        let c_shares = self.share(a * b, rng);
+
+        a_shares
+            .into_iter()
+            .zip(b_shares)
+            .zip(c_shares)
+            .map(|((a, b), c)| AuthenticatedTriple { a, b, c })
+            .collect()
+    }
+}
+
+/// Opens x and checks its MAC, `key_shares` in the order of `shares`
+///
+/// Fails with `MacCheckFailed` if any party's value or MAC was tampered with.
+/// The check is run in the clear here: in the real protocol the σ_i are committed
+/// to before they are revealed, so no party can adapt its σ_i to the others.
+pub fn open<F: PrimeField>(
+    shares: &[AuthenticatedShare<F>],
+    key_shares: &[F],
+) -> Result<F, ShamirError> {
+    if shares.is_empty() || shares.len() != key_shares.len() {
+        return Err(ShamirError::MismatchedShares);
+    }
+
+    let value = shares
+        .iter()
+        .fold(F::zero(), |acc, share| acc + share.value);
+    let check = shares
+        .iter()
+        .zip(key_shares)
+        .fold(F::zero(), |acc, (share, &key_share)| {
+            acc + share.mac - key_share * value
+        });
+
+    if !check.is_zero() {
+        return Err(ShamirError::MacCheckFailed);
+    }
+    Ok(value)
+}
+
+/// ```md
+/// Beaver multiplication on authenticated shares: open d = x - a and e = y - b, checking both
+/// MACs, then
+///
+/// z_i = c_i + d * b_i + e * a_i + d * e
+///
+/// as in `beaver::beaver_multiply`, with the constant d * e added through `add_constant`.
+/// ```
+pub fn multiply<F: PrimeField>(
+    x: &[AuthenticatedShare<F>],
+    y: &[AuthenticatedShare<F>],
+    triple: &[AuthenticatedTriple<F>],
+    key_shares: &[F],
+) -> Result<Vec<AuthenticatedShare<F>>, ShamirError> {
+    if x.len() != y.len() || x.len() != triple.len() || x.len() != key_shares.len() {
+        return Err(ShamirError::MismatchedShares);
+    }
+
+    let d_shares: Vec<_> = x.iter().zip(triple).map(|(x, t)| x.sub(&t.a)).collect();
+    let e_shares: Vec<_> = y.iter().zip(triple).map(|(y, t)| y.sub(&t.b)).collect();
+    let d = open(&d_shares, key_shares)?;
+    let e = open(&e_shares, key_shares)?;
+
+    Ok(triple
+        .iter()
+        .zip(key_shares)
+        .map(|(t, &key_share)| {
+            t.c.add(&t.b.multiply_by_constant(d))
+                .add(&t.a.multiply_by_constant(e))
+                .add_constant(d * e, key_share)
+        })
+        .collect())
+}
+
+/// `parties` values adding up to `value`
+fn additive_split<F: PrimeField>(value: F, parties: u32, rng: &mut impl CryptoRng) -> Vec<F> {
+    let mut values: Vec<F> = (1..parties).map(|_| F::random(rng)).collect();
+    let sum = values.iter().fold(F::zero(), |acc, &v| acc + v);
+    values.push(value - sum);
+    values
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    use super::*;
+    use crate::{Fp, Fp255};
+
+    const PRIME: u64 = 997;
+
+    #[test]
+    fn test_open_and_linear_operations() {
+        type F = Fp<PRIME>;
+        let mut rng = StdRng::seed_from_u64(1);
+        let dealer = Dealer::<F>::new(3, &mut rng).unwrap();
+        let keys = dealer.key_shares();
+
+        let x = dealer.share(F::from_u64(40), &mut rng);
+        let y = dealer.share(F::from_u64(2), &mut rng);
+        assert_eq!(open(&x, keys), Ok(F::from_u64(40)));
+
+        // 3 * (x - y) + 100
+        let z: Vec<_> = x
+            .iter()
+            .zip(&y)
+            .zip(keys)
+            .map(|((x, y), &key)| {
+                x.sub(y)
+                    .multiply_by_constant(F::from_u64(3))
+                    .add_constant(F::from_u64(100), key)
+            })
+            .collect();
+        assert_eq!(open(&z, keys), Ok(F::from_u64(214)));
+    }
+
+    #[test]
+    fn test_tampered_opening_is_detected() {
+        let mut rng = StdRng::seed_from_u64(2);
+        let dealer = Dealer::<Fp255>::new(4, &mut rng).unwrap();
+        let keys = dealer.key_shares();
+        let x = dealer.share(Fp255::from_u64(7), &mut rng);
+
+        // party 3 shifts the value it reveals
+        let mut tampered = x.clone();
+        tampered[2].value += Fp255::from_u64(1);
+        assert_eq!(open(&tampered, keys), Err(ShamirError::MacCheckFailed));
+
+        // adjusting its own MAC share is not enough without knowing α
+        tampered[2].mac += keys[2];
+        assert_eq!(open(&tampered, keys), Err(ShamirError::MacCheckFailed));
+    }
+
+    #[test]
+    fn test_tampered_multiplication_is_detected() {
+        let mut rng = StdRng::seed_from_u64(3);
+        let dealer = Dealer::<Fp255>::new(3, &mut rng).unwrap();
+        let keys = dealer.key_shares();
+        let x = dealer.share(Fp255::from_u64(6), &mut rng);
+        let y = dealer.share(Fp255::from_u64(7), &mut rng);
+        let triple = dealer.triple(&mut rng);
+
+        let z = multiply(&x, &y, &triple, keys).unwrap();
+        assert_eq!(open(&z, keys), Ok(Fp255::from_u64(42)));
+
+        // a party using a different share of a opens a wrong d
+        let mut cheat = triple.clone();
+        cheat[1].a.value += Fp255::from_u64(5);
+        assert_eq!(
+            multiply(&x, &y, &cheat, keys),
+            Err(ShamirError::MacCheckFailed)
+        );
+    }
+
+    #[test]
+    fn test_dot_product() {
+        let mut rng = StdRng::seed_from_u64(4);
+        let dealer = Dealer::<Fp255>::new(3, &mut rng).unwrap();
+        let keys = dealer.key_shares();
+
+        let xs: Vec<Fp255> = (0..10).map(|_| Fp255::random(&mut rng)).collect();
+        let ys: Vec<Fp255> = (0..10).map(|_| Fp255::random(&mut rng)).collect();
+        let expected = xs
+            .iter()
+            .zip(&ys)
+            .fold(Fp255::zero(), |acc, (&x, &y)| acc + x * y);
+
+        let mut sum = dealer.share(Fp255::zero(), &mut rng);
+        for (&x, &y) in xs.iter().zip(&ys) {
+            let x = dealer.share(x, &mut rng);
+            let y = dealer.share(y, &mut rng);
+            let triple = dealer.triple(&mut rng);
+            let product = multiply(&x, &y, &triple, keys).unwrap();
+            sum = sum.iter().zip(&product).map(|(s, p)| s.add(p)).collect();
+        }
+
+        assert_eq!(open(&sum, keys), Ok(expected));
+    }
+}